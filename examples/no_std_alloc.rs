@@ -0,0 +1,24 @@
+//! Builds `TinyVec` in a `no_std` crate, using only the `alloc` crate.
+//!
+//! `cargo build --example no_std_alloc --no-default-features --features alloc`
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use tinyvec::*;
+
+/// Collects the even numbers, spilling to the heap past 4 of them.
+pub fn evens(upto: u32) -> TinyVec<[u32; 4]> {
+  let mut tv = tiny_vec!([u32; 4]);
+  for x in (0..upto).filter(|x| x % 2 == 0) {
+    tv.push(x);
+  }
+  tv
+}
+
+/// Round trips through `Vec`.
+pub fn to_vec(tv: TinyVec<[u32; 4]>) -> Vec<u32> {
+  tv.into_iter().collect()
+}
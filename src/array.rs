@@ -8,7 +8,9 @@
 ///
 /// You are generally **not** expected to need to implement this yourself. It is
 /// already implemented for all the major array lengths (`0..=32` and the powers
-/// of 2 up to 4,096). Additional lengths can easily be added upon request.
+/// of 2 up to 4,096). Additional lengths can easily be added upon request. With
+/// the `rustc_1_55` feature it's instead implemented for every length using
+/// const generics.
 ///
 /// ## Safety Reminder
 ///
@@ -28,14 +30,42 @@ pub trait Array {
   fn as_slice(&self) -> &[Self::Item];
 
   /// Gives a unique slice over the whole thing.
-  /// 
+  ///
   /// A correct implementation will return a slice with a length equal to the
   /// `CAPACITY` value.
   fn as_slice_mut(&mut self) -> &mut [Self::Item];
+
+  /// Makes a new array with every element set to the item type's default.
+  ///
+  /// This is what lets `ArrayVec::new` and friends work without an extra
+  /// `A: Default` bound (the standard library only implements `Default` for
+  /// arrays up to length 32).
+  fn default_array() -> Self;
 }
 
+#[cfg(feature = "rustc_1_55")]
+mod const_generic_impl;
+#[cfg(feature = "rustc_1_55")]
+pub use const_generic_impl::*;
+
+// Builds an array expression of `T::default()` calls. The length is given as
+// binary digits (most significant first): every digit doubles the list built so
+// far, and a `1` digit adds one more element on top of that. This keeps the
+// macro recursion shallow even for the 4,096 element arrays.
+#[cfg(not(feature = "rustc_1_55"))]
+macro_rules! default_array_expr {
+  ([$($acc:tt)*]) => { [$($acc)*] };
+  ([$($acc:tt)*] 0 $($bits:tt)*) => {
+    default_array_expr!([$($acc)* $($acc)*] $($bits)*)
+  };
+  ([$($acc:tt)*] 1 $($bits:tt)*) => {
+    default_array_expr!([$($acc)* $($acc)* T::default(),] $($bits)*)
+  };
+}
+
+#[cfg(not(feature = "rustc_1_55"))]
 macro_rules! impl_array_for_len {
-  ($($len:expr),+ $(,)?) => {
+  ($($len:expr => [$($bits:tt)*]),+ $(,)?) => {
     $(impl<T: Default> Array for [T; $len] {
       type Item = T;
       const CAPACITY: usize = $len;
@@ -47,14 +77,55 @@ macro_rules! impl_array_for_len {
       fn as_slice_mut(&mut self) -> &mut [T] {
         &mut *self
       }
+      #[inline]
+      fn default_array() -> Self {
+        default_array_expr!([] $($bits)*)
+      }
     })+
   }
 }
 
+#[cfg(not(feature = "rustc_1_55"))]
 impl_array_for_len! {
-  0, /* The oft-forgotten 0-length array! */
-  1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
-  17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32,
-  33, /* for luck */
-  64, 128, 256, 512, 1024, 2048, 4096,
+  0 => [], /* The oft-forgotten 0-length array! */
+  1 => [1],
+  2 => [1 0],
+  3 => [1 1],
+  4 => [1 0 0],
+  5 => [1 0 1],
+  6 => [1 1 0],
+  7 => [1 1 1],
+  8 => [1 0 0 0],
+  9 => [1 0 0 1],
+  10 => [1 0 1 0],
+  11 => [1 0 1 1],
+  12 => [1 1 0 0],
+  13 => [1 1 0 1],
+  14 => [1 1 1 0],
+  15 => [1 1 1 1],
+  16 => [1 0 0 0 0],
+  17 => [1 0 0 0 1],
+  18 => [1 0 0 1 0],
+  19 => [1 0 0 1 1],
+  20 => [1 0 1 0 0],
+  21 => [1 0 1 0 1],
+  22 => [1 0 1 1 0],
+  23 => [1 0 1 1 1],
+  24 => [1 1 0 0 0],
+  25 => [1 1 0 0 1],
+  26 => [1 1 0 1 0],
+  27 => [1 1 0 1 1],
+  28 => [1 1 1 0 0],
+  29 => [1 1 1 0 1],
+  30 => [1 1 1 1 0],
+  31 => [1 1 1 1 1],
+  32 => [1 0 0 0 0 0],
+  33 => [1 0 0 0 0 1], /* for luck */
+  64 => [1 0 0 0 0 0 0],
+  128 => [1 0 0 0 0 0 0 0],
+  256 => [1 0 0 0 0 0 0 0 0],
+  512 => [1 0 0 0 0 0 0 0 0 0],
+  1024 => [1 0 0 0 0 0 0 0 0 0 0],
+  2048 => [1 0 0 0 0 0 0 0 0 0 0 0],
+  4096 => [1 0 0 0 0 0 0 0 0 0 0 0 0],
 }
@@ -0,0 +1,42 @@
+use super::*;
+use crate::ArrayVec;
+
+impl<T: Default, const N: usize> Array for [T; N] {
+  type Item = T;
+  const CAPACITY: usize = N;
+  #[inline(always)]
+  fn as_slice(&self) -> &[T] {
+    self
+  }
+  #[inline(always)]
+  fn as_slice_mut(&mut self) -> &mut [T] {
+    self
+  }
+  #[inline]
+  fn default_array() -> Self {
+    [(); N].map(|_| T::default())
+  }
+}
+
+/// An `ArrayVec` written with its item type and capacity given directly.
+///
+/// * Requires the `rustc_1_55` feature
+///
+/// This is just an alias, so it works anywhere that `ArrayVec<[T; N]>` does:
+/// struct fields, trait bounds, and so on.
+///
+/// ```rust
+/// use tinyvec::*;
+///
+/// struct Packet {
+///   payload: ArrayVecN<u8, 12>,
+/// }
+///
+/// let mut p = Packet { payload: ArrayVecN::<u8, 12>::new() };
+/// p.payload.extend_from_slice(&[1, 2, 3]);
+/// assert_eq!(p.payload.capacity(), 12);
+///
+/// let av: ArrayVecN<u8, 16> = array_vec!(u8; 16 => 1, 2, 3);
+/// assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+/// ```
+pub type ArrayVecN<T, const N: usize> = ArrayVec<[T; N]>;
@@ -1,13 +1,13 @@
 use super::*;
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
 /// Helper to make an `ArrayVec`.
 ///
 /// You specify the backing array type, and optionally give all the elements you
 /// want to initially place into the array.
 ///
-/// As an unfortunate restriction, the backing array type must support `Default`
-/// for it to work with this macro.
-///
 /// ```rust
 /// use tinyvec::*;
 /// 
@@ -15,21 +15,113 @@ use super::*;
 /// 
 /// let some_ints = array_vec!([i32; 4], 1, 2, 3);
 /// ```
+///
+/// You can also give the item type and the capacity separately, which saves
+/// you from writing out the array type:
+///
+/// ```rust
+/// use tinyvec::*;
+///
+/// let empty_av = array_vec!(u8; 16);
+/// assert_eq!(empty_av.capacity(), 16);
+///
+/// let some_ints = array_vec!(i32; 4 => 1, 2, 3);
+/// assert_eq!(some_ints.as_slice(), &[1, 2, 3][..]);
+/// ```
+///
+/// Like with `vec!`, you can also repeat a single element some number of
+/// times. The element expression is evaluated once and then cloned as needed.
+///
+/// ```rust
+/// use tinyvec::*;
+///
+/// let zeros = array_vec!([u8; 64] => 0; 33);
+/// assert_eq!(zeros.len(), 33);
+/// assert!(zeros.iter().all(|&z| z == 0));
+/// ```
+///
+/// ## Panics
+/// * If the count given is more than the array's capacity.
+///
+/// Giving more elements in a list than the array can hold is caught at compile
+/// time instead:
+///
+/// ```compile_fail
+/// use tinyvec::*;
+///
+/// let too_many = array_vec!([i32; 2], 1, 2, 3);
+/// ```
 #[macro_export]
 macro_rules! array_vec {
+  // Internal rule, this skips the element count check (`tiny_vec!` does its
+  // own check at runtime).
+  (@fill $array_type:ty, $($elem:expr),*) => {
+    {
+      // Fill in the backing array and then wrap it up all at once, rather than
+      // going through `push` (and its capacity check) for every element.
+      let mut data = <$array_type as $crate::Array>::default_array();
+      #[allow(unused_mut)]
+      let mut len = 0_usize;
+      #[allow(unused_variables)]
+      {
+        let slots = $crate::Array::as_slice_mut(&mut data);
+        $(
+          slots[len] = $elem;
+          len += 1;
+        )*
+      }
+      $crate::ArrayVec::<$array_type>::from_array_len(data, len)
+    }
+  };
   ($array_type:ty) => {
     {
-      let av: ArrayVec<$array_type> = Default::default();
+      let av: $crate::ArrayVec<$array_type> = Default::default();
       av
     }
   };
   ($array_type:ty, $($elem:expr),*) => {
     {
-      let mut av: ArrayVec<$array_type> = Default::default();
-      $( av.push($elem); )*
+      #[allow(clippy::let_unit_value)]
+      let _ = $crate::ArrayVecLiteralCheck::<
+        $array_type,
+        [u8; 0 $( + { let _ = stringify!($elem); 1 } )*],
+      >::ELEMENTS_FIT;
+      $crate::array_vec!(@fill $array_type, $($elem),*)
+    }
+  };
+  ($array_type:ty => $elem:expr; $count:expr) => {
+    {
+      let mut av: $crate::ArrayVec<$array_type> = Default::default();
+      let count: usize = $count;
+      assert!(
+        count <= av.capacity(),
+        "array_vec!> count {} exceeds capacity {}",
+        count,
+        av.capacity()
+      );
+      av.resize(count, $elem);
       av
     }
   };
+  ($item_type:ty; $capacity:expr) => {
+    $crate::array_vec!([$item_type; $capacity])
+  };
+  ($item_type:ty; $capacity:expr => $($elem:expr),*) => {
+    $crate::array_vec!([$item_type; $capacity], $($elem),*)
+  };
+}
+
+/// Used by `array_vec!` to reject element lists that are longer than the
+/// array's capacity at compile time. The `Count` type is a `[u8; N]` where `N`
+/// is the number of elements given.
+#[doc(hidden)]
+pub struct ArrayVecLiteralCheck<A, Count>(
+  core::marker::PhantomData<(A, Count)>,
+);
+impl<A: Array, Count> ArrayVecLiteralCheck<A, Count> {
+  /// Fails to evaluate if there are too many elements.
+  pub const ELEMENTS_FIT: () =
+    [()][(core::mem::size_of::<Count>() > A::CAPACITY) as usize];
 }
 
 /// An array-backed vector-like data structure.
@@ -38,13 +130,76 @@ macro_rules! array_vec {
 /// * Variable length.
 /// * All of the array memory is always "initialized" in the init/uninit memory
 ///   sense.
+///
+/// ## Layout
+///
+/// The layout is part of the API: an `ArrayVec<[T; N]>` is `#[repr(C)]` with
+/// a `usize` length followed by the array, so it matches this C struct:
+///
+/// ```c
+/// struct array_vec_T_N {
+///   size_t len;
+///   T data[N];
+/// };
+/// ```
+///
+/// Every element of the array is a valid `T` at all times, including the
+/// elements past the length, so C code may read the whole array. Use
+/// [`into_raw_parts`](ArrayVec::into_raw_parts) and
+/// [`from_raw_parts`](ArrayVec::from_raw_parts) to move between the two
+/// representations.
 #[repr(C)]
-#[derive(Clone, Copy, Default)]
-pub struct ArrayVec<A: Array> {
+#[derive(Clone, Copy)]
+pub struct ArrayVec<A> {
   len: usize,
   data: A,
 }
 
+impl<A: Array> Default for ArrayVec<A> {
+  #[inline]
+  fn default() -> Self {
+    Self { len: 0, data: A::default_array() }
+  }
+}
+
+impl<A> ArrayVec<A> {
+  /// Wraps an array, using zero as the starting length.
+  ///
+  /// This is a `const fn`, so unlike `new` you can use it for `static` and
+  /// `const` items. The array's elements are only used as the initial values of
+  /// the spare space, so generally you'll pass an array of default values. Any
+  /// item type with a `const` way to make a value works, such as the integers,
+  /// `&str`, or `String` (via `String::new()`).
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  ///
+  /// static SCRATCH: ArrayVec<[u8; 64]> = ArrayVec::from_array_empty([0; 64]);
+  /// assert!(SCRATCH.is_empty());
+  /// assert_eq!(SCRATCH.capacity(), 64);
+  /// ```
+  #[inline(always)]
+  #[must_use]
+  pub const fn from_array_empty(data: A) -> Self {
+    Self { len: 0, data }
+  }
+
+  /// If the vec is empty.
+  #[inline(always)]
+  #[must_use]
+  pub const fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// The length of the vec (in elements).
+  #[inline(always)]
+  #[must_use]
+  pub const fn len(&self) -> usize {
+    self.len
+  }
+}
+
 impl<A: Array> Deref for ArrayVec<A> {
   type Target = [A::Item];
   #[inline(always)]
@@ -66,6 +221,7 @@ impl<A: Array, I: SliceIndex<[A::Item]>> Index<I> for ArrayVec<A> {
   type Output = <I as SliceIndex<[A::Item]>>::Output;
   #[inline(always)]
   #[must_use]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
   fn index(&self, index: I) -> &Self::Output {
     &self.deref()[index]
   }
@@ -74,6 +230,7 @@ impl<A: Array, I: SliceIndex<[A::Item]>> Index<I> for ArrayVec<A> {
 impl<A: Array, I: SliceIndex<[A::Item]>> IndexMut<I> for ArrayVec<A> {
   #[inline(always)]
   #[must_use]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
   fn index_mut(&mut self, index: I) -> &mut Self::Output {
     &mut self.deref_mut()[index]
   }
@@ -83,8 +240,9 @@ impl<A: Array> ArrayVec<A> {
   /// Move all values from `other` into this vec.
   /// 
   /// ## Panics
-  /// * If the vec overflows its capacity
-  /// 
+  /// * If the vec would overflow its capacity. This is checked before anything
+  ///   is moved, so both vecs are left unchanged.
+  ///
   /// ## Example
   /// ```rust
   /// use tinyvec::*;
@@ -95,12 +253,135 @@ impl<A: Array> ArrayVec<A> {
   /// assert_eq!(av2, &[][..]);
   /// ```
   #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
   pub fn append(&mut self, other: &mut Self) {
-    for item in other.drain(..) {
-      self.push(item)
+    let new_len = self.len + other.len;
+    assert!(
+      new_len <= A::CAPACITY,
+      "ArrayVec::append> capacity overflow (len {}, other len {}, capacity {})",
+      self.len,
+      other.len,
+      A::CAPACITY
+    );
+    self.move_all_from(other);
+  }
+
+  /// Move all values from `other` into this vec, where `other` may have a
+  /// different backing array.
+  ///
+  /// ## Panics
+  /// * If the vec would overflow its capacity. This is checked before anything
+  ///   is moved, so both vecs are left unchanged.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut packet = array_vec!([u8; 8], 0xAA, 0x02);
+  /// let mut payload = array_vec!([u8; 4], 1, 2);
+  /// packet.append_from(&mut payload);
+  /// assert_eq!(packet.as_slice(), &[0xAA, 0x02, 1, 2][..]);
+  /// assert!(payload.is_empty());
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn append_from<B: Array<Item = A::Item>>(
+    &mut self, other: &mut ArrayVec<B>,
+  ) {
+    let new_len = self.len + other.len;
+    assert!(
+      new_len <= A::CAPACITY,
+      "ArrayVec::append_from> capacity overflow (len {}, other len {}, capacity {})",
+      self.len,
+      other.len,
+      A::CAPACITY
+    );
+    self.move_all_from(other);
+  }
+
+  /// Moves all of `other` onto the end of this vec. The caller has already
+  /// checked that it fits.
+  #[inline]
+  fn move_all_from<B: Array<Item = A::Item>>(
+    &mut self, other: &mut ArrayVec<B>,
+  ) {
+    let new_len = self.len + other.len;
+    let targets = &mut self.data.as_slice_mut()[self.len..new_len];
+    for (target, item) in targets.iter_mut().zip(other.as_mut_slice()) {
+      *target = replace(item, A::Item::default());
+    }
+    self.len = new_len;
+    other.len = 0;
+  }
+
+  /// Move all values from `other` into this vec, if they all fit.
+  ///
+  /// ## Failure
+  /// * If the vec would overflow its capacity. This is checked before anything
+  ///   is moved, so both vecs are left unchanged.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 4], 1, 2);
+  /// let mut av2 = array_vec!([i32; 4], 3, 4, 5);
+  /// assert!(av.try_append(&mut av2).is_err());
+  /// assert_eq!(av2.pop(), Some(5));
+  /// assert_eq!(av.try_append(&mut av2), Ok(()));
+  /// assert_eq!(av.as_slice(), &[1, 2, 3, 4][..]);
+  /// assert!(av2.is_empty());
+  /// ```
+  #[inline]
+  pub fn try_append(&mut self, other: &mut Self) -> Result<(), CapacityError> {
+    if other.len <= A::CAPACITY - self.len {
+      self.append(other);
+      Ok(())
+    } else {
+      Err(CapacityError::new((), self.len + other.len, A::CAPACITY))
     }
   }
 
+  /// A shared reference to the whole backing array.
+  ///
+  /// The elements past the length are included. They're valid values, but not
+  /// part of the vec.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// // takes a full block, however much of it is used
+  /// fn digest(block: &[u8; 8]) -> u8 {
+  ///   block.iter().fold(0, |acc, b| acc.rotate_left(1) ^ b)
+  /// }
+  /// let av = array_vec!([u8; 8], 1, 2, 3);
+  /// assert_eq!(digest(av.as_inner()), digest(&[1, 2, 3, 0, 0, 0, 0, 0]));
+  /// ```
+  #[inline(always)]
+  #[must_use]
+  pub fn as_inner(&self) -> &A {
+    &self.data
+  }
+
+  /// A mutable reference to the whole backing array.
+  ///
+  /// Changing the elements past the length is allowed, they just aren't part
+  /// of the vec (until the length grows to include them, e.g. with
+  /// [`set_len`](ArrayVec::set_len)).
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([u8; 4], 1, 2);
+  /// av.as_inner_mut()[3] = 9;
+  /// assert_eq!(av.as_slice(), &[1, 2][..]);
+  /// av.set_len(4);
+  /// assert_eq!(av.as_slice(), &[1, 2, 0, 9][..]);
+  /// ```
+  #[inline(always)]
+  #[must_use]
+  pub fn as_inner_mut(&mut self) -> &mut A {
+    &mut self.data
+  }
+
   /// A mutable pointer to the backing array.
   /// 
   /// ## Safety
@@ -152,9 +433,78 @@ impl<A: Array> ArrayVec<A> {
     self.truncate(0)
   }
 
+  /// Overwrites every slot past the length with the item type's default.
+  ///
+  /// [`truncate`](ArrayVec::truncate) (and so `clear`) only moves the length
+  /// for items that don't need dropping, so the old values linger in the
+  /// inactive region, and [`set_len`](ArrayVec::set_len) can bring them back.
+  /// After this, raising the length with `set_len` only exposes defaults.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([u8; 4], 0xAB, 0xCD, 0xEF);
+  /// av.truncate(1);
+  /// av.clear_spare();
+  /// av.set_len(3);
+  /// assert_eq!(av.as_slice(), &[0xAB, 0, 0][..]);
+  /// ```
+  #[inline]
+  pub fn clear_spare(&mut self) {
+    for slot in &mut self.data.as_slice_mut()[self.len..] {
+      *slot = A::Item::default();
+    }
+  }
+
+  /// Moves the items of this vec followed by the items of `other` into a new
+  /// vec, which can have a larger backing array than either of them.
+  ///
+  /// ## Panics
+  /// * If the combined length doesn't fit in `B`. This is checked before
+  ///   anything is moved.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let header = array_vec!([u8; 2], 0xAA, 0x02);
+  /// let payload = array_vec!([u8; 4], 1, 2);
+  /// let packet: ArrayVec<[u8; 6]> = header.concat_into(payload);
+  /// assert_eq!(packet.as_slice(), &[0xAA, 0x02, 1, 2][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn concat_into<B, C>(mut self, mut other: ArrayVec<C>) -> ArrayVec<B>
+  where
+    B: Array<Item = A::Item>,
+    C: Array<Item = A::Item>,
+  {
+    let new_len = self.len + other.len;
+    assert!(
+      new_len <= B::CAPACITY,
+      "ArrayVec::concat_into> capacity overflow (len {}, other len {}, capacity {})",
+      self.len,
+      other.len,
+      B::CAPACITY
+    );
+    let mut out = ArrayVec::<B>::default();
+    out.append_from(&mut self);
+    out.append_from(&mut other);
+    out
+  }
+
   /// De-duplicates the vec.
-  #[cfg(feature = "nightly_slice_partition_dedup")]
-  #[inline(always)]
+  ///
+  /// Consecutive equal elements are removed (and dropped), keeping the first
+  /// of each run.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 8], 1, 1, 2, 3, 3, 3, 1);
+  /// av.dedup();
+  /// assert_eq!(av.as_slice(), &[1, 2, 3, 1][..]);
+  /// ```
+  #[inline]
   pub fn dedup(&mut self)
   where
     A::Item: PartialEq,
@@ -163,22 +513,23 @@ impl<A: Array> ArrayVec<A> {
   }
 
   /// De-duplicates the vec according to the predicate given.
-  #[cfg(feature = "nightly_slice_partition_dedup")]
-  #[inline(always)]
+  ///
+  /// The predicate gets `(current, previous_kept)`, and the current element is
+  /// removed if it returns `true`.
+  #[inline]
   pub fn dedup_by<F>(&mut self, same_bucket: F)
   where
     F: FnMut(&mut A::Item, &mut A::Item) -> bool,
   {
-    let len = {
-      let (dedup, _) = self.as_mut_slice().partition_dedup_by(same_bucket);
-      dedup.len()
-    };
+    #[cfg(feature = "nightly_slice_partition_dedup")]
+    let len = self.as_mut_slice().partition_dedup_by(same_bucket).0.len();
+    #[cfg(not(feature = "nightly_slice_partition_dedup"))]
+    let len = dedup_partition(self.as_mut_slice(), same_bucket);
     self.truncate(len);
   }
 
   /// De-duplicates the vec according to the key selector given.
-  #[cfg(feature = "nightly_slice_partition_dedup")]
-  #[inline(always)]
+  #[inline]
   pub fn dedup_by_key<F, K>(&mut self, mut key: F)
   where
     F: FnMut(&mut A::Item) -> K,
@@ -187,6 +538,50 @@ impl<A: Array> ArrayVec<A> {
     self.dedup_by(|a, b| key(a) == key(b))
   }
 
+  /// Removes all duplicate elements, not just consecutive ones.
+  ///
+  /// The first occurrence of each value is kept and the survivors stay in
+  /// their original order. This is a quadratic scan, which is fine for the
+  /// sizes an `ArrayVec` usually holds, and only needs `PartialEq`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 8], 3, 1, 3, 2, 1, 3);
+  /// av.dedup_unsorted();
+  /// assert_eq!(av.as_slice(), &[3, 1, 2][..]);
+  /// ```
+  #[inline]
+  pub fn dedup_unsorted(&mut self)
+  where
+    A::Item: PartialEq,
+  {
+    let len = dedup_unsorted_partition(self.as_mut_slice(), |a, b| a == b);
+    self.truncate(len);
+  }
+
+  /// Removes all elements whose key was already seen earlier in the vec.
+  ///
+  /// See [`dedup_unsorted`](ArrayVec::<A>::dedup_unsorted).
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 8], 1, -1, 2, -2, 3);
+  /// av.dedup_unsorted_by_key(|x| x.abs());
+  /// assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+  /// ```
+  #[inline]
+  pub fn dedup_unsorted_by_key<F, K>(&mut self, mut key: F)
+  where
+    F: FnMut(&A::Item) -> K,
+    K: PartialEq,
+  {
+    let len =
+      dedup_unsorted_partition(self.as_mut_slice(), |a, b| key(a) == key(b));
+    self.truncate(len);
+  }
+
   /// Creates a draining iterator that removes the specified range in the vector
   /// and yields the removed items.
   ///
@@ -206,6 +601,7 @@ impl<A: Array> ArrayVec<A> {
   /// assert_eq!(av.as_slice(), &[]);
   /// ```
   #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
   pub fn drain<R: RangeBounds<usize>>(
     &mut self,
     range: R,
@@ -223,118 +619,698 @@ impl<A: Array> ArrayVec<A> {
     };
     assert!(
       start <= end,
-      "ArrayVec::drain> Illegal range, {} to {}",
+      "ArrayVec::drain> illegal range {} to {} (len {}, capacity {})",
       start,
-      end
+      end,
+      self.len,
+      A::CAPACITY
     );
     assert!(
       end <= self.len,
-      "ArrayVec::drain> Range ends at {} but length is only {}!",
+      "ArrayVec::drain> range end {} is out of bounds (len {}, capacity {})",
       end,
-      self.len
+      self.len,
+      A::CAPACITY
     );
+    let old_len = replace(&mut self.len, start);
     ArrayVecDrain {
       parent: self,
+      target_start: start,
       target_index: start,
-      target_count: end - start,
+      target_end: end,
+      range_end: end,
+      old_len,
     }
   }
 
-  // LATER(Vec): drain_filter #nightly https://github.com/rust-lang/rust/issues/43244
+  /// Moves elements out of the slice and into this vec, leaving default values
+  /// behind, until either the slice is done or this vec is full.
+  ///
+  /// Returns how many elements were moved. Those are always the first ones of
+  /// the slice, the rest are left where they are.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 4], 1);
+  /// let mut scratch = [2, 3, 4, 5];
+  /// assert_eq!(av.extend_by_taking(&mut scratch), 3);
+  /// assert_eq!(av.as_slice(), &[1, 2, 3, 4][..]);
+  /// assert_eq!(scratch, [0, 0, 0, 5]);
+  /// ```
+  #[inline]
+  pub fn extend_by_taking(&mut self, src: &mut [A::Item]) -> usize {
+    let count = src.len().min(A::CAPACITY - self.len);
+    let targets = &mut self.data.as_slice_mut()[self.len..self.len + count];
+    for (target, item) in targets.iter_mut().zip(src.iter_mut()) {
+      *target = replace(item, A::Item::default());
+    }
+    self.len += count;
+    count
+  }
 
   /// Clone each element of the slice into this vec.
+  ///
+  /// This is one capacity check and then a single `clone_from_slice` into the
+  /// spare slots, which for `Copy` items is a plain memory copy.
+  ///
+  /// ## Panics
+  /// * If the slice doesn't fit in the vec's spare capacity. This is checked
+  ///   before anything is cloned, so the vec is left unchanged.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut packet = array_vec!([u8; 8], 0xAA);
+  /// packet.extend_from_slice(&[1, 2, 3]);
+  /// assert_eq!(packet.as_slice(), &[0xAA, 1, 2, 3][..]);
+  /// ```
   #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
   pub fn extend_from_slice(&mut self, sli: &[A::Item])
   where
     A::Item: Clone,
   {
-    for i in sli {
-      self.push(i.clone())
-    }
+    assert!(
+      sli.len() <= A::CAPACITY - self.len,
+      "ArrayVec::extend_from_slice> {} items don't fit (len {}, capacity {})",
+      sli.len(),
+      self.len,
+      A::CAPACITY
+    );
+    let new_len = self.len + sli.len();
+    self.data.as_slice_mut()[self.len..new_len].clone_from_slice(sli);
+    self.len = new_len;
   }
 
-  /// Wraps up an array and uses the given length as the initial length.
+  /// Clones the elements in the range `src` onto the end of the vec.
   ///
-  /// Note that the `From` impl for arrays assumes the full length is used.
+  /// Like `Vec::extend_from_within`, the range refers to the elements as they
+  /// were before the call (so it can't reach into the part being added). For
+  /// an LZ77 style copy whose length is longer than its distance, call this
+  /// repeatedly.
   ///
   /// ## Panics
+  /// * If the start of the range is greater than the end.
+  /// * If the end of the range is past the edge of the vec.
+  /// * If the range doesn't fit in the vec's spare capacity. This is checked
+  ///   before anything is cloned, so the vec is left unchanged.
   ///
-  /// The length must be less than or equal to the capacity of the array.
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([u8; 8], b'a', b'b');
+  /// av.extend_from_within(..);
+  /// av.extend_from_within(1..3);
+  /// assert_eq!(av.as_slice(), b"ababba");
+  /// ```
   #[inline]
-  #[must_use]
-  #[allow(clippy::match_wild_err_arm)]
-  pub fn from_array_len(data: A, len: usize) -> Self {
-    match Self::try_from_array_len(data, len) {
-      Ok(out) => out,
-      Err(_) => {
-        panic!("ArrayVec: length {} exceeds capacity {}!", len, A::CAPACITY)
-      }
-    }
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn extend_from_within<R: RangeBounds<usize>>(&mut self, src: R)
+  where
+    A::Item: Clone,
+  {
+    use core::ops::Bound;
+    let start = match src.start_bound() {
+      Bound::Included(x) => *x,
+      Bound::Excluded(x) => x + 1,
+      Bound::Unbounded => 0,
+    };
+    let end = match src.end_bound() {
+      Bound::Included(x) => x + 1,
+      Bound::Excluded(x) => *x,
+      Bound::Unbounded => self.len,
+    };
+    assert!(
+      start <= end,
+      "ArrayVec::extend_from_within> illegal range {} to {} (len {}, capacity {})",
+      start,
+      end,
+      self.len,
+      A::CAPACITY
+    );
+    assert!(
+      end <= self.len,
+      "ArrayVec::extend_from_within> range end {} is out of bounds (len {}, capacity {})",
+      end,
+      self.len,
+      A::CAPACITY
+    );
+    let n = end - start;
+    assert!(
+      n <= A::CAPACITY - self.len,
+      "ArrayVec::extend_from_within> {} items don't fit (len {}, capacity {})",
+      n,
+      self.len,
+      A::CAPACITY
+    );
+    let (active, spare) = self.data.as_slice_mut().split_at_mut(self.len);
+    spare[..n].clone_from_slice(&active[start..end]);
+    self.len += n;
   }
 
-  /// Inserts an item at the position given, moving all following elements +1
-  /// index.
+  /// Clone each element of the slice into this vec, if they all fit.
   ///
-  /// ## Panics
-  /// * If `index` > `len`
+  /// This is all or nothing: either the whole slice is appended, or the vec is
+  /// left unchanged.
+  ///
+  /// ## Failure
+  /// * If the slice doesn't fit in the vec's spare capacity. Nothing is cloned.
   ///
   /// ## Example
   /// ```rust
   /// use tinyvec::*;
-  /// let mut av = array_vec!([i32; 10], 1, 2, 3);
-  /// av.insert(1, 4);
-  /// assert_eq!(av.as_slice(), &[1, 4, 2, 3]);
-  /// av.insert(4, 5);
-  /// assert_eq!(av.as_slice(), &[1, 4, 2, 3, 5]);
+  /// let mut av = array_vec!([i32; 4], 1);
+  /// assert_eq!(av.try_extend_from_slice(&[2, 3]), Ok(()));
+  /// assert!(av.try_extend_from_slice(&[4, 5]).is_err());
+  /// assert_eq!(av.as_slice(), &[1, 2, 3][..]);
   /// ```
   #[inline]
-  pub fn insert(&mut self, index: usize, item: A::Item) {
-    use core::cmp::Ordering;
-    match index.cmp(&self.len) {
-      Ordering::Less => {
-        let targets: &mut [A::Item] = &mut self.as_mut_slice()[index..];
-        let mut temp = item;
-        for target in targets.iter_mut() {
-          temp = replace(target, temp);
-        }
-        self.push(temp);
-      }
-      Ordering::Equal => {
-        self.push(item);
-      }
-      Ordering::Greater => {
-        panic!(
-          "ArrayVec::insert> index {} is out of bounds {}",
-          index, self.len
-        );
+  pub fn try_extend_from_slice(
+    &mut self, sli: &[A::Item],
+  ) -> Result<(), CapacityError>
+  where
+    A::Item: Clone,
+  {
+    if sli.len() <= A::CAPACITY - self.len {
+      self.extend_from_slice(sli);
+      Ok(())
+    } else {
+      Err(CapacityError::new((), self.len + sli.len(), A::CAPACITY))
+    }
+  }
+
+  /// Clone each element of the slice into this vec, without checking the
+  /// capacity.
+  ///
+  /// * Requires the `unchecked_ops` feature
+  ///
+  /// ## Safety
+  /// * The length of the vec plus the length of the slice must be less than or
+  ///   equal to the vec's capacity.
+  /// * The `Array` impl of the backing array must be correct (the impls that
+  ///   this crate provides are).
+  #[cfg(feature = "unchecked_ops")]
+  #[allow(unsafe_code)]
+  #[inline]
+  pub unsafe fn extend_from_slice_unchecked(&mut self, sli: &[A::Item])
+  where
+    A::Item: Clone,
+  {
+    debug_assert!(self.len + sli.len() <= A::CAPACITY);
+    let new_len = self.len + sli.len();
+    self
+      .data
+      .as_slice_mut()
+      .get_unchecked_mut(self.len..new_len)
+      .clone_from_slice(sli);
+    self.len = new_len;
+  }
+
+  /// Creates an iterator that removes and yields the elements for which the
+  /// predicate returns `true`, keeping the others in order.
+  ///
+  /// This is a single pass over the vec. If the iterator is dropped early, the
+  /// elements it didn't get to are all kept.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 8], 1, 2, 3, 4, 5, 6);
+  /// let evens: Vec<i32> = av.extract_if(|x| *x % 2 == 0).collect();
+  /// assert_eq!(evens, vec![2, 4, 6]);
+  /// assert_eq!(av.as_slice(), &[1, 3, 5][..]);
+  /// ```
+  #[inline]
+  pub fn extract_if<F: FnMut(&mut A::Item) -> bool>(
+    &mut self, pred: F,
+  ) -> ArrayVecExtractIf<'_, A, F> {
+    let old_len = self.len;
+    ArrayVecExtractIf { parent: self, index: 0, removed: 0, old_len, pred }
+  }
+
+  /// Pushes items from the iterator until either it runs out or this vec is
+  /// full, then gives back the iterator.
+  ///
+  /// No item is pulled from the iterator once the vec is full, so the
+  /// returned iterator yields exactly the items that didn't fit.
+  ///
+  /// (This isn't called `fill`, so that `av.fill(value)` still reaches the
+  /// slice method through `DerefMut`.)
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 4], 1);
+  /// let mut rest = av.fill_from_iter(2..7);
+  /// assert_eq!(av.as_slice(), &[1, 2, 3, 4][..]);
+  /// assert_eq!(rest.next(), Some(5));
+  /// ```
+  #[inline]
+  pub fn fill_from_iter<I: IntoIterator<Item = A::Item>>(
+    &mut self, iter: I,
+  ) -> I::IntoIter {
+    let mut iter = iter.into_iter();
+    while self.len < A::CAPACITY {
+      match iter.next() {
+        Some(item) => self.push(item),
+        None => break,
       }
     }
+    iter
   }
 
-  /// If the vec is empty.
-  #[inline(always)]
+  /// Makes a full vec, with each element made by a call to `f`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut n = 0;
+  /// let av: ArrayVec<[i32; 4]> = ArrayVec::filled_with(|| {
+  ///   n += 1;
+  ///   n * n
+  /// });
+  /// assert_eq!(av.as_slice(), &[1, 4, 9, 16][..]);
+  /// ```
+  #[inline]
   #[must_use]
-  pub fn is_empty(&self) -> bool {
-    self.len == 0
+  pub fn filled_with<F: FnMut() -> A::Item>(mut f: F) -> Self {
+    let mut data = A::default_array();
+    for slot in data.as_slice_mut() {
+      *slot = f();
+    }
+    Self { data, len: A::CAPACITY }
   }
 
-  /// The length of the vec (in elements).
+  /// Wraps up an array and uses the given length as the initial length.
+  ///
+  /// Note that the `From` impl for arrays assumes the full length is used.
+  ///
+  /// ## Panics
+  ///
+  /// The length must be less than or equal to the capacity of the array.
+  #[inline]
+  #[must_use]
+  #[allow(clippy::match_wild_err_arm)]
+  #[cfg(not(feature = "rustc_1_61"))]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn from_array_len(data: A, len: usize) -> Self {
+    match Self::try_from_array_len(data, len) {
+      Ok(out) => out,
+      Err(_) => panic!(
+        "ArrayVec::from_array_len> length {} exceeds capacity {}",
+        len,
+        A::CAPACITY
+      ),
+    }
+  }
+
+  /// Wraps up an array and uses the given length as the initial length.
+  ///
+  /// Note that the `From` impl for arrays assumes the full length is used.
+  ///
+  /// This is a `const fn` (with the `rustc_1_61` feature), so it can be used
+  /// for `static` and `const` items, and a bad length is then a compile error.
+  ///
+  /// ## Panics
+  ///
+  /// The length must be less than or equal to the capacity of the array.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  ///
+  /// const PRIMES: ArrayVec<[u32; 8]> =
+  ///   ArrayVec::from_array_len([2, 3, 5, 7, 0, 0, 0, 0], 4);
+  /// assert_eq!(PRIMES.as_slice(), &[2, 3, 5, 7][..]);
+  /// ```
+  #[inline]
+  #[must_use]
+  #[cfg(feature = "rustc_1_61")]
+  #[track_caller]
+  pub const fn from_array_len(data: A, len: usize) -> Self {
+    if len <= A::CAPACITY {
+      Self { len, data }
+    } else {
+      core::mem::forget(data);
+      // `const fn` panics can't format their arguments yet.
+      panic!("ArrayVec::from_array_len> length exceeds capacity")
+    }
+  }
+
+  /// Makes a full vec, with every element a clone of `elem`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let av: ArrayVec<[String; 2]> = ArrayVec::from_elem(String::from("a"));
+  /// assert_eq!(av.as_slice(), &["a", "a"][..]);
+  /// ```
+  #[inline]
+  #[must_use]
+  pub fn from_elem(elem: A::Item) -> Self
+  where
+    A::Item: Clone,
+  {
+    Self::filled_with(|| elem.clone())
+  }
+
+  /// Rebuilds a vec from the array and length that
+  /// [`into_raw_parts`](ArrayVec::into_raw_parts) gave you, such as after
+  /// passing them through C code.
+  ///
+  /// This is the same as [`from_array_len`](ArrayVec::from_array_len).
+  ///
+  /// ## Panics
+  /// * If `len` is greater than the capacity of the array.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let av = array_vec!([u8; 4], 1, 2);
+  /// let (data, len) = av.into_raw_parts();
+  /// assert_eq!((data, len), ([1, 2, 0, 0], 2));
+  /// let av = ArrayVec::from_raw_parts(data, len);
+  /// assert_eq!(av.as_slice(), &[1, 2][..]);
+  /// ```
+  #[inline]
+  #[must_use]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn from_raw_parts(data: A, len: usize) -> Self {
+    Self::from_array_len(data, len)
+  }
+
+  /// Rebuilds a vec from an array and length without checking the length
+  /// against the capacity.
+  ///
+  /// * Requires the `unchecked_ops` feature
+  ///
+  /// ## Safety
+  /// * `len` must be less than or equal to the capacity of the array. The safe
+  ///   methods will still panic on a bad length, but the other `_unchecked`
+  ///   methods will cause undefined behavior.
+  #[cfg(feature = "unchecked_ops")]
+  #[allow(unsafe_code)]
   #[inline(always)]
   #[must_use]
-  pub fn len(&self) -> usize {
-    self.len
+  pub unsafe fn from_raw_parts_unchecked(data: A, len: usize) -> Self {
+    debug_assert!(len <= A::CAPACITY);
+    Self { len, data }
   }
 
-  /// Makes a new, empty vec.
+  /// Inserts an item at the position given, moving all following elements +1
+  /// index.
+  ///
+  /// ## Panics
+  /// * If `index` > `len`
+  /// * If the vec is already at capacity (the vec is left unchanged)
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 10], 1, 2, 3);
+  /// av.insert(1, 4);
+  /// assert_eq!(av.as_slice(), &[1, 4, 2, 3]);
+  /// av.insert(4, 5);
+  /// assert_eq!(av.as_slice(), &[1, 4, 2, 3, 5]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn insert(&mut self, index: usize, item: A::Item) {
+    assert!(
+      index <= self.len,
+      "ArrayVec::insert> index {} is out of bounds (len {}, capacity {})",
+      index,
+      self.len,
+      A::CAPACITY
+    );
+    assert!(
+      self.len < A::CAPACITY,
+      "ArrayVec::insert> capacity overflow (len {}, capacity {})",
+      self.len,
+      A::CAPACITY
+    );
+    // Shift the tail up by one in a single pass, which brings the spare slot
+    // just past the end down to `index`, then fill that slot.
+    let targets = &mut self.data.as_slice_mut()[index..=self.len];
+    targets.rotate_right(1);
+    targets[0] = item;
+    self.len += 1;
+  }
+
+  /// Inserts clones of the slice's elements at the position given, moving all
+  /// following elements up by the slice's length.
+  ///
+  /// The tail is moved once, however long the slice is.
+  ///
+  /// ## Panics
+  /// * If `index` > `len`
+  /// * If the slice doesn't fit in the vec's spare capacity (the vec is left
+  ///   unchanged)
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 8], 1, 2, 3);
+  /// av.insert_from_slice(1, &[7, 8]);
+  /// assert_eq!(av.as_slice(), &[1, 7, 8, 2, 3][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn insert_from_slice(&mut self, index: usize, sli: &[A::Item])
+  where
+    A::Item: Clone,
+  {
+    assert!(
+      index <= self.len,
+      "ArrayVec::insert_from_slice> index {} is out of bounds (len {}, capacity {})",
+      index,
+      self.len,
+      A::CAPACITY
+    );
+    assert!(
+      sli.len() <= A::CAPACITY - self.len,
+      "ArrayVec::insert_from_slice> {} items don't fit (len {}, capacity {})",
+      sli.len(),
+      self.len,
+      A::CAPACITY
+    );
+    // Rotating brings the spare slots just past the end down to `index`, then
+    // the clones go into them.
+    let new_len = self.len + sli.len();
+    let targets = &mut self.data.as_slice_mut()[index..new_len];
+    targets.rotate_right(sli.len());
+    targets[..sli.len()].clone_from_slice(sli);
+    self.len = new_len;
+  }
+
+  /// Inserts an item into a sorted vec, keeping it sorted, and returns the
+  /// index it went to.
+  ///
+  /// The item goes after any elements equal to it, so the order of equal
+  /// elements is the order they were inserted in.
+  ///
+  /// ## Panics
+  /// * If the vec is already at capacity (the vec is left unchanged)
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 8], 1, 3, 5);
+  /// assert_eq!(av.insert_sorted(4), 2);
+  /// assert_eq!(av.insert_sorted(0), 0);
+  /// assert_eq!(av.as_slice(), &[0, 1, 3, 4, 5][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn insert_sorted(&mut self, item: A::Item) -> usize
+  where
+    A::Item: Ord,
+  {
+    self.insert_sorted_by(item, Ord::cmp)
+  }
+
+  /// Inserts an item into a vec sorted by the comparator given, keeping it
+  /// sorted, and returns the index it went to.
+  ///
+  /// See [`insert_sorted`](ArrayVec::<A>::insert_sorted).
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn insert_sorted_by<F>(&mut self, item: A::Item, mut compare: F) -> usize
+  where
+    F: FnMut(&A::Item, &A::Item) -> core::cmp::Ordering,
+  {
+    use core::cmp::Ordering;
+    // Treating equal as less puts the new item after all the equal ones.
+    let found = self.binary_search_by(|probe| match compare(probe, &item) {
+      Ordering::Equal => Ordering::Less,
+      other => other,
+    });
+    let index = match found {
+      Ok(i) | Err(i) => i,
+    };
+    self.insert(index, item);
+    index
+  }
+
+  /// Inserts an item into a vec sorted by the key given, keeping it sorted,
+  /// and returns the index it went to.
+  ///
+  /// See [`insert_sorted`](ArrayVec::<A>::insert_sorted).
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn insert_sorted_by_key<F, K>(
+    &mut self, item: A::Item, mut key: F,
+  ) -> usize
+  where
+    F: FnMut(&A::Item) -> K,
+    K: Ord,
+  {
+    self.insert_sorted_by(item, |a, b| key(a).cmp(&key(b)))
+  }
+
+  /// Gives back the backing array, if the vec is full.
+  ///
+  /// Unlike [`into_inner`](ArrayVec::into_inner) there's no default padding:
+  /// every element of the array is one that was in the vec.
+  ///
+  /// ## Failure
+  /// * If the length isn't exactly the capacity, you get the vec back in the
+  ///   `Err`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let av = array_vec!([i32; 3], 1, 2);
+  /// let mut av = av.into_array().unwrap_err();
+  /// av.push(3);
+  /// assert_eq!(av.into_array(), Ok([1, 2, 3]));
+  /// ```
+  #[inline]
+  pub fn into_array(self) -> Result<A, Self> {
+    if self.len == A::CAPACITY {
+      Ok(self.data)
+    } else {
+      Err(self)
+    }
+  }
+
+  /// Gives back the backing array.
+  ///
+  /// This is the reverse of the `From<A>` impl: the active elements are at
+  /// `[0, len)`, and every slot past that is set to the default value (use
+  /// [`into_raw_parts`](ArrayVec::into_raw_parts) to keep the length and the
+  /// inactive slots as they are).
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([u8; 4], 1, 2, 3);
+  /// av.truncate(2);
+  /// assert_eq!(av.into_inner(), [1, 2, 0, 0]);
+  /// ```
+  #[inline]
+  #[must_use]
+  pub fn into_inner(mut self) -> A {
+    for slot in &mut self.data.as_slice_mut()[self.len..] {
+      *slot = A::Item::default();
+    }
+    self.data
+  }
+
+  /// Splits the vec into its backing array and its length.
+  ///
+  /// The whole array is returned, including the elements past the length
+  /// (which are all still valid values).
+  ///
+  /// Unlike [`into_inner`](ArrayVec::into_inner) nothing is lost:
+  /// [`from_raw_parts`](ArrayVec::from_raw_parts) on the result gives back
+  /// exactly the same vec, inactive slots included.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let av = array_vec!([i32; 4], 1, 2);
+  /// let (data, len) = av.clone().into_raw_parts();
+  /// assert_eq!(ArrayVec::from_raw_parts(data, len), av);
+  /// ```
   #[inline(always)]
   #[must_use]
-  pub fn new() -> Self
+  pub fn into_raw_parts(self) -> (A, usize) {
+    (self.data, self.len)
+  }
+
+  /// Turns each element into a new value, collecting them into a vec of the
+  /// new item type.
+  ///
+  /// The closure is called once per element, in order.
+  ///
+  /// ## Panics
+  /// * If the length of this vec is more than the capacity of `B`. This is
+  ///   checked before the closure is ever called.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let av = array_vec!([u16; 8], 1, 2, 3);
+  /// let wide: ArrayVec<[u32; 8]> = av.map(|x| u32::from(x) << 16);
+  /// assert_eq!(wide.as_slice(), &[1 << 16, 2 << 16, 3 << 16][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn map<B, F>(self, mut f: F) -> ArrayVec<B>
   where
-    A: Default,
+    B: Array,
+    F: FnMut(A::Item) -> B::Item,
   {
+    match self.try_map(|x| Ok::<B::Item, core::convert::Infallible>(f(x))) {
+      Ok(out) => out,
+      Err(never) => match never {},
+    }
+  }
+
+  /// Makes a new, empty vec.
+  ///
+  /// This can't be a `const fn`, since the array is made with
+  /// [`Array::default_array`]. For `static` and `const` items use
+  /// [`from_array_empty`](ArrayVec::from_array_empty) instead.
+  #[inline(always)]
+  #[must_use]
+  pub fn new() -> Self {
     Self::default()
   }
 
+  /// Splits the vec in two by the predicate: the elements it returns `true`
+  /// for go in the first vec, the others in the second.
+  ///
+  /// Both keep the original relative order. The elements are moved, not
+  /// cloned.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let av = array_vec!([i32; 8], 1, 2, 3, 4, 5);
+  /// let (even, odd) = av.partition(|x| *x % 2 == 0);
+  /// assert_eq!(even.as_slice(), &[2, 4][..]);
+  /// assert_eq!(odd.as_slice(), &[1, 3, 5][..]);
+  /// ```
+  #[inline]
+  #[must_use]
+  pub fn partition<F: FnMut(&A::Item) -> bool>(
+    mut self, mut f: F,
+  ) -> (Self, Self) {
+    let mut matching = Self::default();
+    let mut rest = Self::default();
+    for item in self.drain(..) {
+      if f(&item) {
+        matching.push(item)
+      } else {
+        rest.push(item)
+      }
+    }
+    (matching, rest)
+  }
+
   /// Remove and return the last element of the vec, if there is one.
   /// 
   /// ## Failure
@@ -351,338 +1327,1387 @@ impl<A: Array> ArrayVec<A> {
     }
   }
 
-  /// Place an element onto the end of the vec.
-  /// 
-  /// ## Panics
-  /// * If the length of the vec would overflow the capacity.
-  #[inline(always)]
-  pub fn push(&mut self, val: A::Item) {
+  /// Removes and returns the last element, if the predicate returns `true`
+  /// for it.
+  ///
+  /// The predicate gets a mutable reference, so it can also change the
+  /// element, and it's called at most once.
+  ///
+  /// ## Failure
+  /// * If the vec is empty, or the predicate returns `false`, you get `None`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 4], 1, 2, 0);
+  /// assert_eq!(av.pop_if(|x| *x == 0), Some(0));
+  /// assert_eq!(av.pop_if(|x| *x == 0), None);
+  /// assert_eq!(av.as_slice(), &[1, 2][..]);
+  /// ```
+  #[inline]
+  pub fn pop_if<F: FnOnce(&mut A::Item) -> bool>(
+    &mut self, f: F,
+  ) -> Option<A::Item> {
+    let last = self.last_mut()?;
+    if f(last) {
+      self.pop()
+    } else {
+      None
+    }
+  }
+
+  /// Place an element onto the end of the vec.
+  /// 
+  /// ## Panics
+  /// * If the length of the vec would overflow the capacity.
+  #[inline(always)]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn push(&mut self, val: A::Item) {
+    if self.len < A::CAPACITY {
+      replace(&mut self.data.as_slice_mut()[self.len], val);
+      self.len += 1;
+    } else {
+      panic!(
+        "ArrayVec::push> capacity overflow (len {}, capacity {})",
+        self.len,
+        A::CAPACITY
+      )
+    }
+  }
+
+  /// Place an element onto the end of the vec, and get a mutable reference to
+  /// it.
+  ///
+  /// ## Panics
+  /// * If the length of the vec would overflow the capacity.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([(i32, i32); 4], (1, 1));
+  /// let last = av.push_and_get((2, 0));
+  /// last.1 = 7;
+  /// assert_eq!(av[1], (2, 7));
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn push_and_get(&mut self, val: A::Item) -> &mut A::Item {
+    self.push(val);
+    &mut self.data.as_slice_mut()[self.len - 1]
+  }
+
+  /// Place an element onto the end of the vec, without checking the capacity.
+  ///
+  /// * Requires the `unchecked_ops` feature
+  ///
+  /// ## Safety
+  /// * The length of the vec must be less than its capacity.
+  /// * The `Array` impl of the backing array must be correct (the impls that
+  ///   this crate provides are).
+  #[cfg(feature = "unchecked_ops")]
+  #[allow(unsafe_code)]
+  #[inline(always)]
+  pub unsafe fn push_unchecked(&mut self, val: A::Item) {
+    debug_assert!(self.len < A::CAPACITY);
+    *self.data.as_slice_mut().get_unchecked_mut(self.len) = val;
+    self.len += 1;
+  }
+
+  /// Removes the item at `index`, shifting all others down by one index.
+  ///
+  /// Returns the removed element.
+  ///
+  /// ## Panics
+  ///
+  /// If the index is out of bounds.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 4], 1, 2, 3);
+  /// assert_eq!(av.remove(1), 2);
+  /// assert_eq!(av.as_slice(), &[1, 3][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn remove(&mut self, index: usize) -> A::Item {
+    assert!(
+      index < self.len,
+      "ArrayVec::remove> index {} is out of bounds (len {}, capacity {})",
+      index,
+      self.len,
+      A::CAPACITY
+    );
+    // Take the item out, then shift the tail down in a single pass, which
+    // moves the default left behind to the last slot.
+    let targets: &mut [A::Item] = &mut self.deref_mut()[index..];
+    let out = replace(&mut targets[0], A::Item::default());
+    targets.rotate_left(1);
+    self.len -= 1;
+    out
+  }
+
+  /// Removes the first element that the predicate returns `true` for,
+  /// shifting all after it down by one index.
+  ///
+  /// ## Failure
+  /// * If no element matches you get `None`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 4], 1, 4, 2, 6);
+  /// assert_eq!(av.remove_if(|x| *x % 2 == 0), Some(4));
+  /// assert_eq!(av.remove_if(|x| *x > 10), None);
+  /// assert_eq!(av.as_slice(), &[1, 2, 6][..]);
+  /// ```
+  #[inline]
+  pub fn remove_if<F: FnMut(&A::Item) -> bool>(
+    &mut self, f: F,
+  ) -> Option<A::Item> {
+    let index = self.iter().position(f)?;
+    Some(self.remove(index))
+  }
+
+  /// Removes the first element equal to `item`, shifting all after it down by
+  /// one index.
+  ///
+  /// ## Failure
+  /// * If no element is equal you get `None`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 4], 1, 2, 3, 2);
+  /// assert_eq!(av.remove_item(&2), Some(2));
+  /// assert_eq!(av.remove_item(&5), None);
+  /// assert_eq!(av.as_slice(), &[1, 3, 2][..]);
+  /// ```
+  #[inline]
+  pub fn remove_item(&mut self, item: &A::Item) -> Option<A::Item>
+  where
+    A::Item: PartialEq,
+  {
+    self.remove_if(|x| x == item)
+  }
+
+  /// Makes a new vec holding the contents of this one `n` times over.
+  ///
+  /// `n == 0` gives an empty vec, and `n == 1` a plain copy.
+  ///
+  /// ## Panics
+  /// * If `len * n` is more than the capacity of `B`. This is checked before
+  ///   anything is cloned.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let sync = array_vec!([u8; 2], 0x55, 0xAA);
+  /// let preamble: ArrayVec<[u8; 8]> = sync.repeat_into(3);
+  /// assert_eq!(preamble.as_slice(), &[0x55, 0xAA, 0x55, 0xAA, 0x55, 0xAA][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn repeat_into<B>(&self, n: usize) -> ArrayVec<B>
+  where
+    B: Array<Item = A::Item>,
+    A::Item: Clone,
+  {
+    let new_len = match self.len.checked_mul(n) {
+      Some(new_len) if new_len <= B::CAPACITY => new_len,
+      _ => panic!(
+        "ArrayVec::repeat_into> {} copies of {} items don't fit (capacity {})",
+        n,
+        self.len,
+        B::CAPACITY
+      ),
+    };
+    let mut out = ArrayVec::<B>::default();
+    let targets = &mut out.data.as_slice_mut()[..new_len];
+    for chunk in targets.chunks_mut(self.len.max(1)) {
+      chunk.clone_from_slice(self.as_slice());
+    }
+    out.len = new_len;
+    out
+  }
+
+  /// Resize the vec to the new length.
+  ///
+  /// If it needs to be longer, it's filled with clones of the provided value.
+  /// If it needs to be shorter, it's truncated.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use tinyvec::*;
+  ///
+  /// let mut av = array_vec!([&str; 10], "hello");
+  /// av.resize(3, "world");
+  /// assert_eq!(av.as_slice(), &["hello", "world", "world"][..]);
+  ///
+  /// let mut av = array_vec!([i32; 10], 1, 2, 3, 4);
+  /// av.resize(2, 0);
+  /// assert_eq!(av.as_slice(), &[1, 2][..]);
+  /// ```
+  #[inline]
+  pub fn resize(&mut self, new_len: usize, new_val: A::Item)
+  where
+    A::Item: Clone,
+  {
+    use core::cmp::Ordering;
+    match new_len.cmp(&self.len) {
+      Ordering::Less => self.truncate(new_len),
+      Ordering::Equal => (),
+      Ordering::Greater => {
+        // Like `Vec::resize`, the last new slot gets `new_val` itself.
+        while self.len + 1 < new_len {
+          self.push(new_val.clone());
+        }
+        self.push(new_val);
+      }
+    }
+  }
+
+  /// Resize the vec to the new length.
+  ///
+  /// If it needs to be longer, it's filled with repeated calls to the provided
+  /// function. If it needs to be shorter, it's truncated.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use tinyvec::*;
+  ///
+  /// let mut av = array_vec!([i32; 10], 1, 2, 3);
+  /// av.resize_with(5, Default::default);
+  /// assert_eq!(av.as_slice(), &[1, 2, 3, 0, 0][..]);
+  ///
+  /// let mut av = array_vec!([i32; 10]);
+  /// let mut p = 1;
+  /// av.resize_with(4, || {
+  ///   p *= 2;
+  ///   p
+  /// });
+  /// assert_eq!(av.as_slice(), &[2, 4, 8, 16][..]);
+  /// ```
+  #[inline]
+  pub fn resize_with<F: FnMut() -> A::Item>(
+    &mut self,
+    new_len: usize,
+    mut f: F,
+  ) {
+    use core::cmp::Ordering;
+    match new_len.cmp(&self.len) {
+      Ordering::Less => self.truncate(new_len),
+      Ordering::Equal => (),
+      Ordering::Greater => {
+        while self.len < new_len {
+          self.push(f());
+        }
+      }
+    }
+  }
+
+  /// Walk the vec and keep only the elements that pass the predicate given.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use tinyvec::*;
+  ///
+  /// let mut av = array_vec!([i32; 10], 1, 1, 2, 3, 3, 4);
+  /// av.retain(|&x| x % 2 == 0);
+  /// assert_eq!(av.as_slice(), &[2, 4][..]);
+  /// ```
+  ///
+  /// If the predicate panics, the elements rejected so far are gone and all
+  /// others (including the one the predicate panicked on) stay in the vec, in
+  /// their original order.
+  #[inline]
+  pub fn retain<F: FnMut(&A::Item) -> bool>(&mut self, mut acceptable: F) {
+    self.retain_mut(|x| acceptable(x))
+  }
+
+  /// Like [`retain`](ArrayVec::<A>::retain), but the predicate gets mutable
+  /// access to each element.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use tinyvec::*;
+  ///
+  /// let mut av = array_vec!([i32; 10], 1, 2, 3, 4);
+  /// av.retain_mut(|x| {
+  ///   *x *= 10;
+  ///   *x > 20
+  /// });
+  /// assert_eq!(av.as_slice(), &[30, 40][..]);
+  /// ```
+  #[inline]
+  pub fn retain_mut<F: FnMut(&mut A::Item) -> bool>(
+    &mut self,
+    mut acceptable: F,
+  ) {
+    // Kept elements are swapped down into place as we go, and rejected ones
+    // are dropped right away, so the vacated tail ends up all defaults. The
+    // guard fixes up the length afterwards, even during a panic.
+    let len = self.len;
+    let mut guard = RetainGuard { av: self, len, kept: 0, processed: 0 };
+    while guard.processed < len {
+      let i = guard.processed;
+      let targets = guard.av.data.as_slice_mut();
+      if acceptable(&mut targets[i]) {
+        targets.swap(guard.kept, i);
+        guard.kept += 1;
+      } else {
+        targets[i] = A::Item::default();
+      }
+      guard.processed += 1;
+    }
+  }
+
+  /// Forces the length of the vector to `new_len`.
+  ///
+  /// ## Panics
+  /// If `new_len` is greater than the vec's capacity.
+  ///
+  /// ## Safety
+  /// * This is a fully safe operation! The inactive memory already counts as
+  ///   "initialized" by Rust's rules.
+  /// * Other than "the memory is initialized" there are no other guarantees
+  ///   regarding what you find in the inactive portion of the vec.
+  ///   [`clear_spare`](ArrayVec::clear_spare) resets it to defaults first.
+  #[inline(always)]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn set_len(&mut self, new_len: usize) {
+    if new_len > A::CAPACITY {
+      // Note(Lokathor): Technically we don't have to panic here, and we could
+      // just let some other call later on trigger a panic on accident when the
+      // length is wrong. However, it's a lot easier to catch bugs when things
+      // are more "fail-fast".
+      panic!(
+        "ArrayVec::set_len> length {} exceeds capacity (len {}, capacity {})",
+        new_len,
+        self.len,
+        A::CAPACITY
+      )
+    } else {
+      self.len = new_len;
+    }
+  }
+
+  /// Forces the length of the vector to `new_len`, without checking it against
+  /// the capacity.
+  ///
+  /// * Requires the `unchecked_ops` feature
+  ///
+  /// ## Safety
+  /// * `new_len` must be less than or equal to the vec's capacity. The safe
+  ///   methods will still panic on a bad length, but the other `_unchecked`
+  ///   methods will cause undefined behavior.
+  #[cfg(feature = "unchecked_ops")]
+  #[allow(unsafe_code)]
+  #[inline(always)]
+  pub unsafe fn set_len_unchecked(&mut self, new_len: usize) {
+    debug_assert!(new_len <= A::CAPACITY);
+    self.len = new_len;
+  }
+
+  /// Creates a splicing iterator that removes the specified range in the
+  /// vector, yields the removed items, and replaces them with the items from
+  /// `replace_with`.
+  ///
+  /// Like with `Vec::splice`, the replacement happens when the iterator is
+  /// dropped, whether or not all the removed items were taken out of it. If
+  /// the iterator is leaked instead (e.g. with `mem::forget`) the vec keeps its
+  /// length, with default values in place of the items that were taken out.
+  ///
+  /// ## Panics
+  /// * If the start is greater than the end
+  /// * If the end is past the edge of the vec.
+  /// * (when the splice is dropped) If the replacements don't fit in the
+  ///   removed range plus the spare capacity. The ones that fit are put in
+  ///   place first, so the vec is still in order.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 6], 1, 2, 3);
+  /// let removed: Vec<i32> = av.splice(1..2, vec![7, 8, 9]).collect();
+  /// assert_eq!(removed, vec![2]);
+  /// assert_eq!(av.as_slice(), &[1, 7, 8, 9, 3][..]);
+  ///
+  /// av.splice(..3, None);
+  /// assert_eq!(av.as_slice(), &[9, 3][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn splice<R, I>(
+    &mut self,
+    range: R,
+    replace_with: I,
+  ) -> ArrayVecSplice<'_, A, I::IntoIter>
+  where
+    R: RangeBounds<usize>,
+    I: IntoIterator<Item = A::Item>,
+  {
+    use core::ops::Bound;
+    let start = match range.start_bound() {
+      Bound::Included(x) => *x,
+      Bound::Excluded(x) => x + 1,
+      Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+      Bound::Included(x) => x + 1,
+      Bound::Excluded(x) => *x,
+      Bound::Unbounded => self.len,
+    };
+    assert!(
+      start <= end,
+      "ArrayVec::splice> illegal range {} to {} (len {}, capacity {})",
+      start,
+      end,
+      self.len,
+      A::CAPACITY
+    );
+    assert!(
+      end <= self.len,
+      "ArrayVec::splice> range end {} is out of bounds (len {}, capacity {})",
+      end,
+      self.len,
+      A::CAPACITY
+    );
+    ArrayVecSplice {
+      parent: self,
+      removal_start: start,
+      removal_end: end,
+      target_index: start,
+      target_end: end,
+      replacement: replace_with.into_iter(),
+    }
+  }
+
+  /// Splits the collection at the point given.
+  ///
+  /// * `[0, at)` stays in this vec
+  /// * `[at, len)` ends up in the new vec.
+  ///
+  /// ## Panics
+  /// * if at > len
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 4], 1, 2, 3);
+  /// let av2 = av.split_off(1);
+  /// assert_eq!(av.as_slice(), &[1][..]);
+  /// assert_eq!(av2.as_slice(), &[2, 3][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn split_off(&mut self, at: usize) -> Self {
+    if at > self.len {
+      panic!(
+        "ArrayVec::split_off> index {} is out of bounds (len {}, capacity {})",
+        at,
+        self.len,
+        A::CAPACITY
+      );
+    }
+    // The new vec's slots are all defaults already, so swapping the tail over
+    // leaves defaults behind in our vacated slots.
+    let mut new = Self::default();
+    let moves = &mut self.as_mut_slice()[at..];
+    moves.swap_with_slice(&mut new.data.as_slice_mut()[..moves.len()]);
+    new.len = self.len - at;
+    self.len = at;
+    new
+  }
+
+  /// Splits off the last `n` elements into a new vec.
+  ///
+  /// This is [`split_off`](ArrayVec::<A>::split_off) counted from the back:
+  /// the elements keep their order, and are moved over in one go.
+  ///
+  /// ## Panics
+  /// * if n > len
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 4], 1, 2, 3, 4);
+  /// let back = av.split_off_back(3);
+  /// assert_eq!(av.as_slice(), &[1][..]);
+  /// assert_eq!(back.as_slice(), &[2, 3, 4][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn split_off_back(&mut self, n: usize) -> Self {
+    assert!(
+      n <= self.len,
+      "ArrayVec::split_off_back> count {} is out of bounds (len {}, capacity {})",
+      n,
+      self.len,
+      A::CAPACITY
+    );
+    self.split_off(self.len - n)
+  }
+
+  /// Remove an element, swapping the end of the vec into its place.
+  ///
+  /// ## Panics
+  /// * If the index is out of bounds.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([&str; 4], "foo", "bar", "quack", "zap");
+  ///
+  /// assert_eq!(av.swap_remove(1), "bar");
+  /// assert_eq!(av.as_slice(), &["foo", "zap", "quack"][..]);
+  ///
+  /// assert_eq!(av.swap_remove(0), "foo");
+  /// assert_eq!(av.as_slice(), &["quack", "zap"][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn swap_remove(&mut self, index: usize) -> A::Item {
+    assert!(
+      index < self.len,
+      "ArrayVec::swap_remove> index {} is out of bounds (len {}, capacity {})",
+      index,
+      self.len,
+      A::CAPACITY
+    );
+    if index == self.len - 1 {
+        self.pop().unwrap()
+    } else {
+        let i = self.pop().unwrap();
+        replace(&mut self[index], i)
+    }
+  }
+
+  /// Removes the first element that the predicate returns `true` for, swapping
+  /// the end of the vec into its place.
+  ///
+  /// ## Failure
+  /// * If no element matches you get `None`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 4], 1, 4, 2, 6);
+  /// assert_eq!(av.swap_remove_if(|x| *x % 2 == 0), Some(4));
+  /// assert_eq!(av.as_slice(), &[1, 6, 2][..]);
+  /// ```
+  #[inline]
+  pub fn swap_remove_if<F: FnMut(&A::Item) -> bool>(
+    &mut self, f: F,
+  ) -> Option<A::Item> {
+    let index = self.iter().position(f)?;
+    Some(self.swap_remove(index))
+  }
+
+  /// Removes the first element equal to `item`, swapping the end of the vec
+  /// into its place.
+  ///
+  /// ## Failure
+  /// * If no element is equal you get `None`.
+  #[inline]
+  pub fn swap_remove_item(&mut self, item: &A::Item) -> Option<A::Item>
+  where
+    A::Item: PartialEq,
+  {
+    self.swap_remove_if(|x| x == item)
+  }
+
+  /// Reduces the vec's length to the given value.
+  /// 
+  /// If the vec is already shorter than the input, nothing happens.
+  #[inline]
+  pub fn truncate(&mut self, new_len: usize) {
+    if new_len >= self.len {
+      return;
+    }
+    let old_len = replace(&mut self.len, new_len);
+    if needs_drop::<A::Item>() {
+      for doomed in &mut self.data.as_slice_mut()[new_len..old_len] {
+        *doomed = A::Item::default();
+      }
+    }
+  }
+
+  /// Wraps an array, using the given length as the starting length.
+  ///
+  /// If you want to use the whole length of the array, you can just use the
+  /// `From` impl.
+  ///
+  /// ## Failure
+  ///
+  /// If the given length is greater than the capacity of the array this will
+  /// error, and you'll get the array back in the `Err`.
+  #[inline]
+  pub fn try_from_array_len(data: A, len: usize) -> Result<Self, A> {
+    if len <= A::CAPACITY {
+      Ok(Self { data, len })
+    } else {
+      Err(data)
+    }
+  }
+
+  /// Wraps an array, using the given length as the starting length.
+  ///
+  /// Same as [`try_from_array_len`](ArrayVec::<A>::try_from_array_len), but
+  /// the array comes back inside a [`CapacityError`].
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let err = ArrayVec::from_array_len_checked([1, 2, 3], 4).unwrap_err();
+  /// assert_eq!(err.requested_len(), 4);
+  /// assert_eq!(err.element(), [1, 2, 3]);
+  /// ```
+  #[inline]
+  pub fn from_array_len_checked(
+    data: A, len: usize,
+  ) -> Result<Self, CapacityError<A>> {
+    Self::try_from_array_len(data, len)
+      .map_err(|data| CapacityError::new(data, len, A::CAPACITY))
+  }
+
+  /// Inserts an item at the position given, moving all following elements +1
+  /// index, if there's room for it.
+  ///
+  /// ## Failure
+  /// * If the vec is already at capacity you get the item back in the `Err`,
+  ///   and the vec is left unchanged.
+  ///
+  /// ## Panics
+  /// * If `index` > `len`
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 3], 1, 2);
+  /// assert_eq!(av.try_insert(0, 3), Ok(()));
+  /// assert_eq!(av.try_insert(1, 4), Err(4));
+  /// assert_eq!(av.as_slice(), &[3, 1, 2][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn try_insert(
+    &mut self, index: usize, item: A::Item,
+  ) -> Result<(), A::Item> {
+    assert!(
+      index <= self.len,
+      "ArrayVec::try_insert> index {} is out of bounds (len {}, capacity {})",
+      index,
+      self.len,
+      A::CAPACITY
+    );
+    if self.len < A::CAPACITY {
+      self.insert(index, item);
+      Ok(())
+    } else {
+      Err(item)
+    }
+  }
+
+  /// Inserts clones of the slice's elements at the position given, if they
+  /// fit.
+  ///
+  /// Otherwise the same as
+  /// [`insert_from_slice`](ArrayVec::<A>::insert_from_slice).
+  ///
+  /// ## Failure
+  /// * If the slice doesn't fit in the vec's spare capacity. Nothing is cloned.
+  ///
+  /// ## Panics
+  /// * If `index` > `len`
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 4], 1, 2);
+  /// assert!(av.try_insert_from_slice(0, &[7, 8, 9]).is_err());
+  /// assert_eq!(av.try_insert_from_slice(0, &[7, 8]), Ok(()));
+  /// assert_eq!(av.as_slice(), &[7, 8, 1, 2][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn try_insert_from_slice(
+    &mut self, index: usize, sli: &[A::Item],
+  ) -> Result<(), CapacityError>
+  where
+    A::Item: Clone,
+  {
+    assert!(
+      index <= self.len,
+      "ArrayVec::try_insert_from_slice> index {} is out of bounds (len {}, capacity {})",
+      index,
+      self.len,
+      A::CAPACITY
+    );
+    if sli.len() <= A::CAPACITY - self.len {
+      self.insert_from_slice(index, sli);
+      Ok(())
+    } else {
+      Err(CapacityError::new((), self.len + sli.len(), A::CAPACITY))
+    }
+  }
+
+  /// Inserts an item into a sorted vec, keeping it sorted, and returns the
+  /// index it went to, if there's room.
+  ///
+  /// Otherwise the same as [`insert_sorted`](ArrayVec::<A>::insert_sorted).
+  ///
+  /// ## Failure
+  /// * If the vec is already at capacity you get the item back in the `Err`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 3], 1, 5);
+  /// assert_eq!(av.try_insert_sorted(3), Ok(1));
+  /// assert_eq!(av.try_insert_sorted(2), Err(2));
+  /// assert_eq!(av.as_slice(), &[1, 3, 5][..]);
+  /// ```
+  #[inline]
+  pub fn try_insert_sorted(&mut self, item: A::Item) -> Result<usize, A::Item>
+  where
+    A::Item: Ord,
+  {
+    if self.len < A::CAPACITY {
+      Ok(self.insert_sorted(item))
+    } else {
+      Err(item)
+    }
+  }
+
+  /// Turns each element into a new value with a fallible closure, collecting
+  /// them into a vec of the new item type.
+  ///
+  /// The closure is called once per element, in order, stopping at the first
+  /// error.
+  ///
+  /// ## Failure
+  /// * The first error the closure returns. The elements not yet mapped are
+  ///   dropped, as are the ones already mapped.
+  ///
+  /// ## Panics
+  /// * If the length of this vec is more than the capacity of `B`. This is
+  ///   checked before the closure is ever called.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// use core::convert::TryFrom;
+  /// let av = array_vec!([i32; 4], 1, 2, 3);
+  /// let bytes: Result<ArrayVec<[u8; 4]>, _> = av.try_map(u8::try_from);
+  /// assert_eq!(bytes.unwrap().as_slice(), &[1, 2, 3][..]);
+  ///
+  /// let av = array_vec!([i32; 4], 1, -2, 3);
+  /// let bytes: Result<ArrayVec<[u8; 4]>, _> = av.try_map(u8::try_from);
+  /// assert!(bytes.is_err());
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn try_map<B, F, E>(self, mut f: F) -> Result<ArrayVec<B>, E>
+  where
+    B: Array,
+    F: FnMut(A::Item) -> Result<B::Item, E>,
+  {
+    assert!(
+      self.len <= B::CAPACITY,
+      "ArrayVec::try_map> {} items don't fit (capacity {})",
+      self.len,
+      B::CAPACITY
+    );
+    let mut out = ArrayVec::<B>::default();
+    for (target, item) in out.data.as_slice_mut().iter_mut().zip(self) {
+      *target = f(item)?;
+      out.len += 1;
+    }
+    Ok(out)
+  }
+
+  /// Place an element onto the end of the vec, and get a mutable reference to
+  /// it, if there's room.
+  ///
+  /// ## Failure
+  /// * If the vec is full you get the element back in the `Err`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 2], 1);
+  /// *av.try_push_and_get(2).unwrap() += 10;
+  /// assert_eq!(av.try_push_and_get(3), Err(3));
+  /// assert_eq!(av.as_slice(), &[1, 12][..]);
+  /// ```
+  #[inline]
+  pub fn try_push_and_get(
+    &mut self, val: A::Item,
+  ) -> Result<&mut A::Item, A::Item> {
     if self.len < A::CAPACITY {
-      replace(&mut self.data.as_slice_mut()[self.len], val);
-      self.len += 1;
+      Ok(self.push_and_get(val))
     } else {
-      panic!("ArrayVec: overflow!")
+      Err(val)
     }
   }
 
-  /// Removes the item at `index`, shifting all others down by one index.
-  ///
-  /// Returns the removed element.
-  ///
-  /// ## Panics
+  /// Removes the item at `index`, shifting all others down by one index, if
+  /// the index is in bounds.
   ///
-  /// If the index is out of bounds.
+  /// Otherwise the same as [`remove`](ArrayVec::<A>::remove).
   ///
   /// ## Example
-  ///
   /// ```rust
   /// use tinyvec::*;
   /// let mut av = array_vec!([i32; 4], 1, 2, 3);
-  /// assert_eq!(av.remove(1), 2);
+  /// assert_eq!(av.try_remove(3), None);
+  /// assert_eq!(av.try_remove(1), Some(2));
   /// assert_eq!(av.as_slice(), &[1, 3][..]);
   /// ```
   #[inline]
-  pub fn remove(&mut self, index: usize) -> A::Item {
-    let targets: &mut [A::Item] = &mut self.deref_mut()[index..];
-    let mut spare = A::Item::default();
-    for target in targets.iter_mut().rev() {
-      spare = replace(target, spare);
+  pub fn try_remove(&mut self, index: usize) -> Option<A::Item> {
+    if index < self.len {
+      Some(self.remove(index))
+    } else {
+      None
     }
-    self.len -= 1;
-    spare
   }
 
-  // NIGHTLY: remove_item, https://github.com/rust-lang/rust/issues/40062
-
-  /// Resize the vec to the new length.
+  /// Resize the vec to the new length, if it fits.
   ///
-  /// If it needs to be longer, it's filled with clones of the provided value.
-  /// If it needs to be shorter, it's truncated.
+  /// Otherwise the same as [`resize`](ArrayVec::<A>::resize). Shrinking
+  /// always works.
   ///
-  /// ## Example
+  /// ## Failure
+  /// * If `new_len` is more than the capacity. The vec is left unchanged.
   ///
+  /// ## Example
   /// ```rust
   /// use tinyvec::*;
-  ///
-  /// let mut av = array_vec!([&str; 10], "hello");
-  /// av.resize(3, "world");
-  /// assert_eq!(av.as_slice(), &["hello", "world", "world"][..]);
-  ///
-  /// let mut av = array_vec!([i32; 10], 1, 2, 3, 4);
-  /// av.resize(2, 0);
-  /// assert_eq!(av.as_slice(), &[1, 2][..]);
+  /// let mut av = array_vec!([i32; 4], 1);
+  /// assert!(av.try_resize(5, 0).is_err());
+  /// assert_eq!(av.try_resize(3, 0), Ok(()));
+  /// assert_eq!(av.as_slice(), &[1, 0, 0][..]);
   /// ```
   #[inline]
-  pub fn resize(&mut self, new_len: usize, new_val: A::Item)
+  pub fn try_resize(
+    &mut self, new_len: usize, new_val: A::Item,
+  ) -> Result<(), CapacityError>
   where
     A::Item: Clone,
   {
-    use core::cmp::Ordering;
-    match new_len.cmp(&self.len) {
-      Ordering::Less => self.truncate(new_len),
-      Ordering::Equal => (),
-      Ordering::Greater => {
-        while self.len < new_len {
-          self.push(new_val.clone());
-        }
-      }
+    if new_len <= A::CAPACITY {
+      self.resize(new_len, new_val);
+      Ok(())
+    } else {
+      Err(CapacityError::new((), new_len, A::CAPACITY))
     }
   }
 
-  /// Resize the vec to the new length.
+  /// Resize the vec to the new length, if it fits.
   ///
-  /// If it needs to be longer, it's filled with repeated calls to the provided
-  /// function. If it needs to be shorter, it's truncated.
+  /// Otherwise the same as [`resize_with`](ArrayVec::<A>::resize_with).
+  /// Shrinking always works.
   ///
-  /// ## Example
+  /// ## Failure
+  /// * If `new_len` is more than the capacity. The vec is left unchanged and
+  ///   `f` isn't called.
   ///
+  /// ## Example
   /// ```rust
   /// use tinyvec::*;
-  ///
-  /// let mut av = array_vec!([i32; 10], 1, 2, 3);
-  /// av.resize_with(5, Default::default);
-  /// assert_eq!(av.as_slice(), &[1, 2, 3, 0, 0][..]);
-  ///
-  /// let mut av = array_vec!([i32; 10]);
-  /// let mut p = 1;
-  /// av.resize_with(4, || {
-  ///   p *= 2;
-  ///   p
-  /// });
-  /// assert_eq!(av.as_slice(), &[2, 4, 8, 16][..]);
+  /// let mut av = array_vec!([i32; 4], 1);
+  /// assert!(av.try_resize_with(5, || unreachable!()).is_err());
+  /// assert_eq!(av.try_resize_with(3, Default::default), Ok(()));
+  /// assert_eq!(av.as_slice(), &[1, 0, 0][..]);
   /// ```
   #[inline]
-  pub fn resize_with<F: FnMut() -> A::Item>(
-    &mut self,
-    new_len: usize,
-    mut f: F,
-  ) {
-    use core::cmp::Ordering;
-    match new_len.cmp(&self.len) {
-      Ordering::Less => self.truncate(new_len),
-      Ordering::Equal => (),
-      Ordering::Greater => {
-        while self.len < new_len {
-          self.push(f());
-        }
-      }
+  pub fn try_resize_with<F: FnMut() -> A::Item>(
+    &mut self, new_len: usize, f: F,
+  ) -> Result<(), CapacityError> {
+    if new_len <= A::CAPACITY {
+      self.resize_with(new_len, f);
+      Ok(())
+    } else {
+      Err(CapacityError::new((), new_len, A::CAPACITY))
     }
   }
 
-  /// Walk the vec and keep only the elements that pass the predicate given.
+  /// Remove an element, swapping the end of the vec into its place, if the
+  /// index is in bounds.
   ///
-  /// ## Example
+  /// Otherwise the same as [`swap_remove`](ArrayVec::<A>::swap_remove).
   ///
+  /// ## Example
   /// ```rust
   /// use tinyvec::*;
-  ///
-  /// let mut av = array_vec!([i32; 10], 1, 1, 2, 3, 3, 4);
-  /// av.retain(|&x| x % 2 == 0);
-  /// assert_eq!(av.as_slice(), &[2, 4][..]);
+  /// let mut av = array_vec!([i32; 4], 1, 2, 3);
+  /// assert_eq!(av.try_swap_remove(3), None);
+  /// assert_eq!(av.try_swap_remove(0), Some(1));
+  /// assert_eq!(av.as_slice(), &[3, 2][..]);
   /// ```
   #[inline]
-  pub fn retain<F: FnMut(&A::Item) -> bool>(&mut self, mut acceptable: F) {
-    let mut i = 0;
-    while i < self.len {
-      if !acceptable(&self[i]) {
-        self.remove(i);
-      } else {
-        i += 1;
-      }
+  pub fn try_swap_remove(&mut self, index: usize) -> Option<A::Item> {
+    if index < self.len {
+      Some(self.swap_remove(index))
+    } else {
+      None
     }
   }
 
-  /// Forces the length of the vector to `new_len`.
+  /// Obtain the shared slice of the array _after_ the active memory.
+  /// 
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 4]);
+  /// assert_eq!(av.grab_spare_slice().len(), 4);
+  /// av.push(10);
+  /// av.push(11);
+  /// av.push(12);
+  /// av.push(13);
+  /// assert_eq!(av.grab_spare_slice().len(), 0);
+  /// ```
+  #[inline(always)]
+  pub fn grab_spare_slice(&self) -> &[A::Item] {
+    &self.data.as_slice()[self.len..]
+  }
+  
+  /// Obtain the mutable slice of the array _after_ the active memory.
+  /// 
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 4]);
+  /// assert_eq!(av.grab_spare_slice_mut().len(), 4);
+  /// av.push(10);
+  /// av.push(11);
+  /// assert_eq!(av.grab_spare_slice_mut().len(), 2);
+  /// ```
+  #[inline(always)]
+  pub fn grab_spare_slice_mut(&mut self) -> &mut [A::Item] {
+    &mut self.data.as_slice_mut()[self.len..]
+  }
+
+  /// Obtain the mutable slices of the active memory and of the array _after_
+  /// it, at the same time.
   ///
-  /// ## Panics
-  /// If `new_len` is greater than the vec's capacity.
+  /// This lets you fill the spare slots while reading what's already there,
+  /// then [`set_len`](ArrayVec::set_len) to take them in.
   ///
-  /// ## Safety
-  /// * This is a fully safe operation! The inactive memory already counts as
-  ///   "initialized" by Rust's rules.
-  /// * Other than "the memory is initialized" there are no other guarantees
-  ///   regarding what you find in the inactive portion of the vec.
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut av = array_vec!([i32; 4], 1, 2);
+  /// let (active, spare) = av.split_at_spare_mut();
+  /// spare[0] = active[0] + active[1];
+  /// av.set_len(3);
+  /// assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+  /// ```
   #[inline(always)]
-  pub fn set_len(&mut self, new_len: usize) {
-    if new_len > A::CAPACITY {
-      // Note(Lokathor): Technically we don't have to panic here, and we could
-      // just let some other call later on trigger a panic on accident when the
-      // length is wrong. However, it's a lot easier to catch bugs when things
-      // are more "fail-fast".
-      panic!("ArrayVec: set_len overflow!")
-    } else {
-      self.len = new_len;
-    }
+  pub fn split_at_spare_mut(&mut self) -> (&mut [A::Item], &mut [A::Item]) {
+    let len = self.len;
+    self.data.as_slice_mut().split_at_mut(len)
   }
+}
 
-  /// Splits the collection at the point given.
+#[cfg(feature = "rustc_1_55")]
+impl<T: Default, const N: usize> ArrayVec<[T; N]> {
+  /// Makes a vec from an array that's no longer than the capacity.
   ///
-  /// * `[0, at)` stays in this vec
-  /// * `[at, len)` ends up in the new vec.
+  /// The length is `M`, and the slots past that are defaults. (A `From` impl
+  /// can't do this, it would overlap the `From<A>` impl when `M == N`.)
+  ///
+  /// * Requires the `rustc_1_55` feature
   ///
   /// ## Panics
-  /// * if at > len
+  /// * If `M` is more than `N`.
   ///
   /// ## Example
-  ///
   /// ```rust
+  /// # #[cfg(feature = "rustc_1_55")] {
   /// use tinyvec::*;
-  /// let mut av = array_vec!([i32; 4], 1, 2, 3);
-  /// let av2 = av.split_off(1);
-  /// assert_eq!(av.as_slice(), &[1][..]);
-  /// assert_eq!(av2.as_slice(), &[2, 3][..]);
+  /// let av: ArrayVec<[u8; 16]> = ArrayVec::from_partial_array([1, 2, 3]);
+  /// assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+  /// assert_eq!(av.capacity(), 16);
+  /// # }
   /// ```
   #[inline]
-  pub fn split_off(&mut self, at: usize) -> Self
-  where
-    Self: Default,
-  {
-    // FIXME: should this just use drain into the output?
-    if at > self.len {
-      panic!(
-        "ArrayVec::split_off> at value {} exceeds length of {}",
-        at, self.len
-      );
-    }
-    let mut new = Self::default();
-    let moves = &mut self.as_mut_slice()[at..];
-    let targets = new.data.as_slice_mut();
-    for (m, t) in moves.iter_mut().zip(targets) {
-      replace(t, replace(m, A::Item::default()));
+  #[must_use]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn from_partial_array<const M: usize>(arr: [T; M]) -> Self {
+    assert!(
+      M <= N,
+      "ArrayVec::from_partial_array> {} items don't fit (capacity {})",
+      M,
+      N
+    );
+    let mut av = Self::new();
+    for (slot, item) in av.data.iter_mut().zip(IntoIterator::into_iter(arr)) {
+      *slot = item;
     }
-    new.len = self.len - at;
-    self.len = at;
-    new
+    av.len = M;
+    av
   }
+}
 
-  /// Remove an element, swapping the end of the vec into its place.
+impl<A: Array> ArrayVec<A>
+where
+  A::Item: Array,
+{
+  /// Moves the elements of every inner array, in order, into one flat vec.
+  ///
+  /// The inner arrays are all used in full, so the new length is `len` times
+  /// the inner capacity.
+  ///
+  /// Note that the inner arrays are the items here, and items must implement
+  /// [`Default`], so the inner arrays can only be as long as `core` has a
+  /// `Default` impl for.
   ///
   /// ## Panics
-  /// * If the index is out of bounds.
+  /// * If the flat length is more than the capacity of `B`. This is checked
+  ///   before anything is moved.
   ///
   /// ## Example
   /// ```rust
   /// use tinyvec::*;
-  /// let mut av = array_vec!([&str; 4], "foo", "bar", "quack", "zap");
-  ///
-  /// assert_eq!(av.swap_remove(1), "bar");
-  /// assert_eq!(av.as_slice(), &["foo", "zap", "quack"][..]);
-  ///
-  /// assert_eq!(av.swap_remove(0), "foo");
-  /// assert_eq!(av.as_slice(), &["quack", "zap"][..]);
+  /// let frames = array_vec!([[i32; 3]; 4], [1, 2, 3], [4, 5, 6]);
+  /// let flat: ArrayVec<[i32; 12]> = frames.flatten_into();
+  /// assert_eq!(flat.as_slice(), &[1, 2, 3, 4, 5, 6][..]);
   /// ```
   #[inline]
-  pub fn swap_remove(&mut self, index: usize) -> A::Item {
-    assert!(
-      index < self.len,
-      "ArrayVec::swap_remove> index {} is out of bounds {}",
-      index,
-      self.len
-    );
-    if index == self.len - 1 {
-        self.pop().unwrap()
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn flatten_into<B>(self) -> ArrayVec<B>
+  where
+    B: Array<Item = <A::Item as Array>::Item>,
+  {
+    let inner = <A::Item as Array>::CAPACITY;
+    let new_len = match self.len.checked_mul(inner) {
+      Some(new_len) if new_len <= B::CAPACITY => new_len,
+      _ => panic!(
+        "ArrayVec::flatten_into> {} arrays of {} items don't fit (capacity {})",
+        self.len,
+        inner,
+        B::CAPACITY
+      ),
+    };
+    let mut out = ArrayVec::<B>::default();
+    let targets = out.data.as_slice_mut()[..new_len].chunks_mut(inner.max(1));
+    for (chunk, mut arr) in targets.zip(self) {
+      for (target, item) in chunk.iter_mut().zip(arr.as_slice_mut()) {
+        *target = replace(item, B::Item::default());
+      }
+    }
+    out.len = new_len;
+    out
+  }
+}
+
+/// Moves the first element of each run of duplicates to the front of the
+/// slice, keeping their order, and returns how many there are. The duplicates
+/// end up in the rest of the slice, in no particular order.
+///
+/// This is the stable stand-in for `slice::partition_dedup_by`, with the same
+/// `same_bucket(current, previous_kept)` argument order.
+pub(crate) fn dedup_partition<T, F>(sli: &mut [T], mut same_bucket: F) -> usize
+where
+  F: FnMut(&mut T, &mut T) -> bool,
+{
+  if sli.len() <= 1 {
+    return sli.len();
+  }
+  let mut write = 1;
+  for read in 1..sli.len() {
+    let (kept, rest) = sli.split_at_mut(read);
+    if !same_bucket(&mut rest[0], &mut kept[write - 1]) {
+      sli.swap(read, write);
+      write += 1;
+    }
+  }
+  write
+}
+
+/// Moves the first occurrence of every distinct element to the front of the
+/// slice, in order, and returns how many there are.
+pub(crate) fn dedup_unsorted_partition<T, F>(
+  sli: &mut [T], mut same: F,
+) -> usize
+where
+  F: FnMut(&T, &T) -> bool,
+{
+  let mut write = 0;
+  for read in 0..sli.len() {
+    let (kept, rest) = sli.split_at(read);
+    if !kept[..write].iter().any(|k| same(k, &rest[0])) {
+      sli.swap(read, write);
+      write += 1;
+    }
+  }
+  write
+}
+
+/// Finishes a `retain` pass when dropped, even if the predicate panicked.
+///
+/// `[0, kept)` holds the kept elements, `[kept, processed)` holds defaults, and
+/// `[processed, len)` hasn't been looked at yet.
+struct RetainGuard<'p, A: Array> {
+  av: &'p mut ArrayVec<A>,
+  len: usize,
+  kept: usize,
+  processed: usize,
+}
+impl<'p, A: Array> Drop for RetainGuard<'p, A> {
+  #[inline]
+  fn drop(&mut self) {
+    if self.processed < self.len {
+      let unprocessed = &mut self.av.data.as_slice_mut()[self.kept..self.len];
+      unprocessed.rotate_left(self.processed - self.kept);
+    }
+    self.av.len = self.kept + (self.len - self.processed);
+  }
+}
+
+/// Draining iterator for `ArrayVec`
+///
+/// See [`ArrayVec::drain`](ArrayVec::<A>::drain)
+///
+/// While the drain is alive the parent's length is cut down to the start of
+/// the range. Items are taken out of the range in place (leaving defaults
+/// behind), and when the drain is dropped the rest of the range is dropped and
+/// the tail is moved down to close the gap.
+pub struct ArrayVecDrain<'p, A: Array> {
+  parent: &'p mut ArrayVec<A>,
+  /// Start of the drained range.
+  target_start: usize,
+  /// The next item to yield from the front.
+  target_index: usize,
+  /// One past the next item to yield from the back.
+  target_end: usize,
+  /// End of the drained range.
+  range_end: usize,
+  /// The parent's length before the drain.
+  old_len: usize,
+}
+// NIGHTLY: vec_drain_as_slice, https://github.com/rust-lang/rust/issues/58957
+impl<'p, A: Array> Iterator for ArrayVecDrain<'p, A> {
+  type Item = A::Item;
+  #[inline]
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.target_index < self.target_end {
+      let slot = &mut self.parent.data.as_slice_mut()[self.target_index];
+      self.target_index += 1;
+      Some(replace(slot, A::Item::default()))
     } else {
-        let i = self.pop().unwrap();
-        replace(&mut self[index], i)
+      None
+    }
+  }
+  #[inline(always)]
+  #[must_use]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let s = self.target_end - self.target_index;
+    (s, Some(s))
+  }
+}
+impl<'p, A: Array> DoubleEndedIterator for ArrayVecDrain<'p, A> {
+  #[inline]
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.target_index < self.target_end {
+      self.target_end -= 1;
+      let slot = &mut self.parent.data.as_slice_mut()[self.target_end];
+      Some(replace(slot, A::Item::default()))
+    } else {
+      None
     }
   }
-
-  /// Reduces the vec's length to the given value.
-  /// 
-  /// If the vec is already shorter than the input, nothing happens.
+}
+impl<'p, A: Array> ExactSizeIterator for ArrayVecDrain<'p, A> {}
+impl<'p, A: Array> Drop for ArrayVecDrain<'p, A> {
   #[inline]
-  pub fn truncate(&mut self, new_len: usize) {
+  fn drop(&mut self) {
+    let data = self.parent.data.as_slice_mut();
     if needs_drop::<A::Item>() {
-      while self.len > new_len {
-        self.pop();
+      for unyielded in &mut data[self.target_index..self.target_end] {
+        *unyielded = A::Item::default();
       }
-    } else {
-      self.len = self.len.min(new_len);
     }
+    let removed = self.range_end - self.target_start;
+    data[self.target_start..self.old_len].rotate_left(removed);
+    self.parent.len = self.old_len - removed;
   }
+}
 
-  /// Wraps an array, using the given length as the starting length.
-  ///
-  /// If you want to use the whole length of the array, you can just use the
-  /// `From` impl.
-  ///
-  /// ## Failure
-  ///
-  /// If the given length is greater than the capacity of the array this will
-  /// error, and you'll get the array back in the `Err`.
+/// Splicing iterator for `ArrayVec`
+///
+/// See [`ArrayVec::splice`](ArrayVec::<A>::splice)
+///
+/// While the splice is alive the removed items are taken out of the range in
+/// place (leaving defaults behind). When it's dropped the range is refilled
+/// from the replacements, and the rest of the range removed or the remaining
+/// replacements inserted.
+pub struct ArrayVecSplice<'p, A: Array, I: Iterator<Item = A::Item>> {
+  parent: &'p mut ArrayVec<A>,
+  removal_start: usize,
+  removal_end: usize,
+  /// The next item to yield from the front.
+  target_index: usize,
+  /// One past the next item to yield from the back.
+  target_end: usize,
+  replacement: I,
+}
+impl<'p, A, I> Iterator for ArrayVecSplice<'p, A, I>
+where
+  A: Array,
+  I: Iterator<Item = A::Item>,
+{
+  type Item = A::Item;
   #[inline]
-  pub fn try_from_array_len(data: A, len: usize) -> Result<Self, A> {
-    if len <= A::CAPACITY {
-      Ok(Self { data, len })
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.target_index < self.target_end {
+      let slot = &mut self.parent[self.target_index];
+      self.target_index += 1;
+      Some(replace(slot, A::Item::default()))
     } else {
-      Err(data)
+      None
     }
   }
-
-  /// Obtain the shared slice of the array _after_ the active memory.
-  /// 
-  /// ## Example
-  /// ```rust
-  /// use tinyvec::*;
-  /// let mut av = array_vec!([i32; 4]);
-  /// assert_eq!(av.grab_spare_slice().len(), 4);
-  /// av.push(10);
-  /// av.push(11);
-  /// av.push(12);
-  /// av.push(13);
-  /// assert_eq!(av.grab_spare_slice().len(), 0);
-  /// ```
   #[inline(always)]
-  #[cfg(feature = "grab_spare_slice")]
-  pub fn grab_spare_slice(&self) -> &[A::Item] {
-    &self.data.as_slice()[self.len..]
+  #[must_use]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let s = self.target_end - self.target_index;
+    (s, Some(s))
   }
-  
-  /// Obtain the mutable slice of the array _after_ the active memory.
-  /// 
-  /// ## Example
-  /// ```rust
-  /// use tinyvec::*;
-  /// let mut av = array_vec!([i32; 4]);
-  /// assert_eq!(av.grab_spare_slice_mut().len(), 4);
-  /// av.push(10);
-  /// av.push(11);
-  /// assert_eq!(av.grab_spare_slice_mut().len(), 2);
-  /// ```
-  #[inline(always)]
-  #[cfg(feature = "grab_spare_slice")]
-  pub fn grab_spare_slice_mut(&mut self) -> &mut [A::Item] {
-    &mut self.data.as_slice_mut()[self.len..]
+}
+impl<'p, A, I> DoubleEndedIterator for ArrayVecSplice<'p, A, I>
+where
+  A: Array,
+  I: Iterator<Item = A::Item>,
+{
+  #[inline]
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.target_index < self.target_end {
+      self.target_end -= 1;
+      let slot = &mut self.parent[self.target_end];
+      Some(replace(slot, A::Item::default()))
+    } else {
+      None
+    }
+  }
+}
+impl<'p, A, I> ExactSizeIterator for ArrayVecSplice<'p, A, I>
+where
+  A: Array,
+  I: Iterator<Item = A::Item>,
+{
+}
+impl<'p, A, I> Drop for ArrayVecSplice<'p, A, I>
+where
+  A: Array,
+  I: Iterator<Item = A::Item>,
+{
+  #[inline]
+  fn drop(&mut self) {
+    // Overwrite the removed range first (which drops anything not yielded).
+    let mut filled = self.removal_start;
+    while filled < self.removal_end {
+      match self.replacement.next() {
+        Some(item) => self.parent[filled] = item,
+        None => break,
+      }
+      filled += 1;
+    }
+    if filled < self.removal_end {
+      // Ran out of replacements, so close the gap.
+      self.parent.drain(filled..self.removal_end);
+    } else {
+      // Anything left over goes on the end, as long as there's room, and then
+      // gets rotated into place in front of the tail.
+      let old_len = self.parent.len;
+      let mut overflow = false;
+      for item in &mut self.replacement {
+        if self.parent.len == A::CAPACITY {
+          overflow = true;
+          break;
+        }
+        self.parent.push(item);
+      }
+      let added = self.parent.len - old_len;
+      self.parent[self.removal_end..].rotate_right(added);
+      if overflow {
+        panic!(
+          "ArrayVec::splice> replacements don't fit (len {}, capacity {})",
+          self.parent.len,
+          A::CAPACITY
+        )
+      }
+    }
   }
 }
 
-/// Draining iterator for `ArrayVecDrain`
-/// 
-/// See [`ArrayVecDrain::drain`](ArrayVecDrain::<A>::drain)
-pub struct ArrayVecDrain<'p, A: Array> {
+/// Iterator for `ArrayVec::extract_if`
+///
+/// See [`ArrayVec::extract_if`](ArrayVec::<A>::extract_if)
+///
+/// Kept elements are swapped down into place as the iterator goes, so the
+/// slots of the extracted ones (now defaults) always sit just before the next
+/// element to look at. When it's dropped the unvisited elements are moved down
+/// over those, and the length is fixed.
+pub struct ArrayVecExtractIf<'p, A: Array, F: FnMut(&mut A::Item) -> bool> {
   parent: &'p mut ArrayVec<A>,
-  target_index: usize,
-  target_count: usize,
+  /// The next element to pass to the predicate.
+  index: usize,
+  /// How many elements were extracted so far.
+  removed: usize,
+  old_len: usize,
+  pred: F,
 }
-// GoodFirstIssue: this entire type is correct but slow.
-// NIGHTLY: vec_drain_as_slice, https://github.com/rust-lang/rust/issues/58957
-impl<'p, A: Array> Iterator for ArrayVecDrain<'p, A> {
+impl<'p, A, F> Iterator for ArrayVecExtractIf<'p, A, F>
+where
+  A: Array,
+  F: FnMut(&mut A::Item) -> bool,
+{
   type Item = A::Item;
   #[inline]
   fn next(&mut self) -> Option<Self::Item> {
-    if self.target_count > 0 {
-      let out = self.parent.remove(self.target_index);
-      self.target_count -= 1;
-      Some(out)
-    } else {
-      None
+    let slots = self.parent.data.as_slice_mut();
+    while self.index < self.old_len {
+      let i = self.index;
+      // `index` only moves on once the predicate returns, so if it panics the
+      // element counts as unvisited and is kept.
+      let extract = (self.pred)(&mut slots[i]);
+      self.index += 1;
+      if extract {
+        self.removed += 1;
+        return Some(replace(&mut slots[i], A::Item::default()));
+      } else if self.removed > 0 {
+        slots.swap(i - self.removed, i);
+      }
     }
+    None
+  }
+  #[inline(always)]
+  #[must_use]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (0, Some(self.old_len - self.index))
   }
 }
-impl<'p, A: Array> Drop for ArrayVecDrain<'p, A> {
+impl<'p, A, F> Drop for ArrayVecExtractIf<'p, A, F>
+where
+  A: Array,
+  F: FnMut(&mut A::Item) -> bool,
+{
   #[inline]
   fn drop(&mut self) {
-    for _ in self {}
+    let gap_start = self.index - self.removed;
+    self.parent.data.as_slice_mut()[gap_start..self.old_len]
+      .rotate_left(self.removed);
+    self.parent.len = self.old_len - self.removed;
   }
 }
 
@@ -719,8 +2744,22 @@ impl<A: Array> BorrowMut<[A::Item]> for ArrayVec<A> {
 }
 
 impl<A: Array> Extend<A::Item> for ArrayVec<A> {
+  /// ## Panics
+  /// * If the iterator yields more items than fit in the vec. When the
+  ///   iterator's `size_hint` already says that it won't fit this happens
+  ///   before anything is taken from the iterator, and the vec is unchanged.
   #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
   fn extend<T: IntoIterator<Item = A::Item>>(&mut self, iter: T) {
+    let iter = iter.into_iter();
+    let (lower, _) = iter.size_hint();
+    assert!(
+      lower <= A::CAPACITY - self.len,
+      "ArrayVec::extend> at least {} items don't fit (len {}, capacity {})",
+      lower,
+      self.len,
+      A::CAPACITY
+    );
     for t in iter {
       self.push(t)
     }
@@ -739,19 +2778,123 @@ impl<A: Array> From<A> for ArrayVec<A> {
   }
 }
 
-impl<A: Array + Default> FromIterator<A::Item> for ArrayVec<A> {
+impl<'s, A: Array> TryFrom<&'s [A::Item]> for ArrayVec<A>
+where
+  A::Item: Clone,
+{
+  type Error = CapacityError;
+  /// Clones the slice into a new vec, if it fits.
+  ///
+  /// ## Failure
+  /// * If the slice is longer than the capacity. Nothing is cloned.
+  #[inline]
+  fn try_from(sli: &'s [A::Item]) -> Result<Self, Self::Error> {
+    let mut av = Self::default();
+    av.try_extend_from_slice(sli)?;
+    Ok(av)
+  }
+}
+
+/// Moves the active elements into a new `Vec` (with room for exactly those).
+#[cfg(feature = "alloc")]
+impl<A: Array> From<ArrayVec<A>> for Vec<A::Item> {
+  #[inline]
+  #[must_use]
+  fn from(mut av: ArrayVec<A>) -> Self {
+    let mut v = Vec::with_capacity(av.len());
+    v.extend(av.drain(..));
+    v
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<A: Array> TryFrom<Vec<A::Item>> for ArrayVec<A> {
+  type Error = CapacityError<Vec<A::Item>>;
+  /// Moves the elements of the `Vec` into a new vec, if they fit.
+  ///
+  /// ## Failure
+  /// * If the `Vec` is longer than the capacity. You get it back, untouched,
+  ///   as the error's element.
+  #[inline]
+  fn try_from(v: Vec<A::Item>) -> Result<Self, Self::Error> {
+    if v.len() > A::CAPACITY {
+      let len = v.len();
+      return Err(CapacityError::new(v, len, A::CAPACITY));
+    }
+    let mut av = Self { len: v.len(), data: A::default_array() };
+    for (slot, item) in av.data.as_slice_mut().iter_mut().zip(v) {
+      *slot = item;
+    }
+    Ok(av)
+  }
+}
+
+impl<A: Array> FromIterator<A::Item> for ArrayVec<A> {
+  /// ## Panics
+  /// * If the iterator yields more items than the capacity. When the
+  ///   iterator's `size_hint` already says so this happens before anything is
+  ///   taken from the iterator.
   #[inline]
   #[must_use]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
   fn from_iter<T: IntoIterator<Item = A::Item>>(iter: T) -> Self {
     let mut av = Self::default();
-    for i in iter {
-      av.push(i)
-    }
+    av.extend(iter);
     av
   }
 }
 
+/// Extra methods for iterators.
+///
+/// This is implemented for every `Iterator`, bring it into scope to use them.
+pub trait IteratorExt: Iterator + Sized {
+  /// Collects the items into an `ArrayVec`, if they fit.
+  ///
+  /// Unlike `collect`, which panics when there are too many items, this
+  /// returns an error. The iterator is never advanced past the first item
+  /// that doesn't fit.
+  ///
+  /// ## Failure
+  /// * If there are more than `A::CAPACITY` items. The error holds the full
+  ///   vec of the items that did fit along with the first item that didn't.
+  ///   Since the rest of the iterator isn't consumed, the error's
+  ///   `requested_len` is only a lower bound: the items taken so far plus
+  ///   the lower bound of the rest's `size_hint`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let av = (1..=3).try_collect_arrayvec::<[i32; 4]>().unwrap();
+  /// assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+  ///
+  /// let err = (1..=5).try_collect_arrayvec::<[i32; 4]>().unwrap_err();
+  /// let (av, extra) = err.element();
+  /// assert_eq!(av.as_slice(), &[1, 2, 3, 4][..]);
+  /// assert_eq!(extra, 5);
+  /// ```
+  #[allow(clippy::missing_inline_in_public_items, clippy::type_complexity)]
+  fn try_collect_arrayvec<A: Array<Item = Self::Item>>(
+    self,
+  ) -> Result<ArrayVec<A>, CapacityError<(ArrayVec<A>, Self::Item)>> {
+    let mut av = ArrayVec::new();
+    let mut rest = av.fill_from_iter(self);
+    match rest.next() {
+      Some(extra) => {
+        let requested_len =
+          (A::CAPACITY + 1).saturating_add(rest.size_hint().0);
+        Err(CapacityError::new((av, extra), requested_len, A::CAPACITY))
+      }
+      None => Ok(av),
+    }
+  }
+}
+
+impl<I: Iterator> IteratorExt for I {}
+
 /// Iterator for consuming an `ArrayVec` and returning owned elements.
+///
+/// Elements skipped over with `nth` (and so `skip` and friends) are dropped
+/// right away. Any elements not yet yielded are dropped with the iterator.
 pub struct ArrayVecIterator<A: Array> {
   base: usize,
   len: usize,
@@ -781,21 +2924,56 @@ impl<A: Array> Iterator for ArrayVecIterator<A> {
     self.len - self.base
   }
   #[inline]
+  fn fold<B, F>(mut self, init: B, mut f: F) -> B
+  where
+    F: FnMut(B, Self::Item) -> B,
+  {
+    // One pass over the remaining slots, without updating `base` each step.
+    // Nothing can see the defaults left behind since `self` is consumed, so
+    // for items without drop glue the compiler can generally drop the stores.
+    let remaining = &mut self.data.as_slice_mut()[self.base..self.len];
+    let mut acc = init;
+    for slot in remaining {
+      acc = f(acc, replace(slot, A::Item::default()));
+    }
+    acc
+  }
+  #[inline]
   fn last(mut self) -> Option<Self::Item> {
-    Some(replace(&mut self.data.as_slice_mut()[self.len], A::Item::default()))
+    if self.base < self.len {
+      let last = &mut self.data.as_slice_mut()[self.len - 1];
+      Some(replace(last, A::Item::default()))
+    } else {
+      None
+    }
   }
   #[inline]
   fn nth(&mut self, n: usize) -> Option<A::Item> {
-    let i = self.base + (n - 1);
-    if i < self.len {
-      let out = replace(&mut self.data.as_slice_mut()[i], A::Item::default());
-      self.base = i + 1;
-      Some(out)
+    // Like with `Vec`'s iterator, skipped elements are dropped right away.
+    let skip_end = self.base.saturating_add(n).min(self.len);
+    if needs_drop::<A::Item>() {
+      for skipped in &mut self.data.as_slice_mut()[self.base..skip_end] {
+        *skipped = A::Item::default();
+      }
+    }
+    self.base = skip_end;
+    self.next()
+  }
+}
+impl<A: Array> DoubleEndedIterator for ArrayVecIterator<A> {
+  #[inline]
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.base < self.len {
+      self.len -= 1;
+      let last = &mut self.data.as_slice_mut()[self.len];
+      Some(replace(last, A::Item::default()))
     } else {
       None
     }
   }
 }
+impl<A: Array> ExactSizeIterator for ArrayVecIterator<A> {}
+impl<A: Array> FusedIterator for ArrayVecIterator<A> {}
 
 impl<A: Array> IntoIterator for ArrayVec<A> {
   type Item = A::Item;
@@ -881,159 +3059,95 @@ where
 }
 */
 
-// //
-// Formatting impls
-// //
-
-impl<A: Array> Binary for ArrayVec<A>
-where
-  A::Item: Binary,
-{
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      Binary::fmt(elem, f)?;
-    }
-    write!(f, "]")
-  }
+/// The error for an operation that needs more room than the vec has.
+///
+/// The operations that return this leave the vec unchanged when they fail. If
+/// they were given something to store that they couldn't, it's handed back as
+/// the error's element, otherwise the element is `()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CapacityError<T = ()> {
+  element: T,
+  requested_len: usize,
+  capacity: usize,
 }
 
-impl<A: Array> Debug for ArrayVec<A>
-where
-  A::Item: Debug,
-{
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      Debug::fmt(elem, f)?;
-    }
-    write!(f, "]")
+impl<T> CapacityError<T> {
+  /// Makes an error for an operation that needed `requested_len` elements of
+  /// room out of `capacity`, handing back `element`.
+  #[inline(always)]
+  #[must_use]
+  pub fn new(element: T, requested_len: usize, capacity: usize) -> Self {
+    Self { element, requested_len, capacity }
   }
-}
 
-impl<A: Array> Display for ArrayVec<A>
-where
-  A::Item: Display,
-{
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      Display::fmt(elem, f)?;
-    }
-    write!(f, "]")
+  /// The capacity that was too small.
+  #[inline(always)]
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    self.capacity
   }
-}
 
-impl<A: Array> LowerExp for ArrayVec<A>
-where
-  A::Item: LowerExp,
-{
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      LowerExp::fmt(elem, f)?;
-    }
-    write!(f, "]")
+  /// Gets back the element that couldn't be stored.
+  #[inline(always)]
+  #[must_use]
+  pub fn element(self) -> T {
+    self.element
   }
-}
 
-impl<A: Array> LowerHex for ArrayVec<A>
-where
-  A::Item: LowerHex,
-{
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      LowerHex::fmt(elem, f)?;
-    }
-    write!(f, "]")
+  /// The length the operation would have needed.
+  #[inline(always)]
+  #[must_use]
+  pub fn requested_len(&self) -> usize {
+    self.requested_len
   }
-}
 
-impl<A: Array> Octal for ArrayVec<A>
-where
-  A::Item: Octal,
-{
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      Octal::fmt(elem, f)?;
-    }
-    write!(f, "]")
+  /// Drops the element, keeping the lengths.
+  #[inline(always)]
+  #[must_use]
+  pub fn without_element(self) -> CapacityError {
+    CapacityError::new((), self.requested_len, self.capacity)
   }
 }
 
-impl<A: Array> Pointer for ArrayVec<A>
-where
-  A::Item: Pointer,
-{
+impl<T> Display for CapacityError<T> {
   #[allow(clippy::missing_inline_in_public_items)]
   fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      Pointer::fmt(elem, f)?;
-    }
-    write!(f, "]")
+    write!(
+      f,
+      "insufficient capacity: needs room for {} elements, but the capacity is {}",
+      self.requested_len, self.capacity
+    )
   }
 }
 
-impl<A: Array> UpperExp for ArrayVec<A>
-where
-  A::Item: UpperExp,
-{
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      UpperExp::fmt(elem, f)?;
-    }
-    write!(f, "]")
-  }
-}
+#[cfg(feature = "std")]
+impl<T: Debug> std::error::Error for CapacityError<T> {}
 
-impl<A: Array> UpperHex for ArrayVec<A>
+// //
+// Zeroize impls
+// //
+
+/// Zeroizes the whole backing array, including the inactive slots past `len`,
+/// and sets the length to 0.
+#[cfg(feature = "zeroize")]
+impl<A: Array> zeroize::Zeroize for ArrayVec<A>
 where
-  A::Item: UpperHex,
+  A::Item: zeroize::Zeroize,
 {
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      UpperHex::fmt(elem, f)?;
+  #[inline]
+  fn zeroize(&mut self) {
+    for item in self.data.as_slice_mut() {
+      item.zeroize();
     }
-    write!(f, "]")
+    self.len = 0;
   }
 }
+
+// //
+// Formatting impls
+// //
+
+impl_list_formatting!(
+  ArrayVec;
+  Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex
+);
@@ -1,5 +1,6 @@
 #![no_std]
-#![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "unchecked_ops"), forbid(unsafe_code))]
+#![cfg_attr(feature = "unchecked_ops", deny(unsafe_code))]
 #![cfg_attr(
   feature = "nightly_slice_partition_dedup",
   feature(slice_partition_dedup)
@@ -26,15 +27,25 @@
 //! * [`ArrayVec`](ArrayVec) is an array-backed vec-like structure with a fixed
 //!   capacity. If you try to grow the length past the array's capacity it will
 //!   error or panic (depending on the method used).
+//! * [`SmallArrayVec`](SmallArrayVec) is an `ArrayVec` that stores its length
+//!   as a smaller integer type, for when the size of the vec itself matters.
 //! * (`alloc` feature) [`TinyVec`](TinyVec) is an enum that's either an
 //!   "Inline" `ArrayVec` or a "Heap" `Vec`. If it's Inline and you try to grow
 //!   the `ArrayVec` beyond its array capacity it will quietly transition into
 //!   Heap mode and then continue the operation.
 //!
+//! The crate is always `no_std`. The `alloc` feature only needs the `alloc`
+//! crate, so `TinyVec` also works in `no_std` programs that have a global
+//! allocator. The `std` feature (which implies `alloc`) adds the few things
+//! that need the full standard library.
+//!
 //! ## Crate Goals
 //!
 //! 1) The crate is 100% safe code. Not just a safe API, there are also no
 //!    `unsafe` internals. `#![forbid(unsafe_code)]`.
+//!    * The one exception is the off-by-default `unchecked_ops` feature,
+//!      which adds a few `unsafe fn` methods that skip the capacity checks.
+//!      Without that feature the crate still forbids `unsafe` entirely.
 //! 2) No required dependencies.
 //!    * We might provide optional dependencies for extra functionality (eg:
 //!      `serde` compatability).
@@ -54,25 +65,57 @@
 //!      2.y.z version. Not the end of the world.
 //!    * Some methods of `Vec` are simply inappropriate and will not be
 //!      implemented here. For example, `ArrayVec` cannot possibly implement
+//!      the pointer based
 //!      [`from_raw_parts`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.from_raw_parts).
+//!      (`ArrayVec::from_raw_parts` takes the array itself instead.)
 
 use core::{
   borrow::{Borrow, BorrowMut},
   cmp::PartialEq,
-  convert::AsMut,
+  convert::{AsMut, TryFrom},
   default::Default,
   fmt::{
     Binary, Debug, Display, Formatter, LowerExp, LowerHex, Octal, Pointer,
     UpperExp, UpperHex,
   },
-  iter::{Extend, FromIterator, IntoIterator, Iterator},
+  iter::{Extend, FromIterator, FusedIterator, IntoIterator, Iterator},
   mem::{needs_drop, replace},
   ops::{Deref, DerefMut, Index, IndexMut, RangeBounds},
   slice::SliceIndex,
 };
 
 #[cfg(feature = "alloc")]
-extern crate alloc;
+#[doc(hidden)] // used by the `tiny_vec!` macro
+pub extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+/// Implements each of the given formatting traits for a vec type, writing the
+/// active elements as a list (`[a, b, c]`) using that same trait for each
+/// element. Both vec types use this, so their output always matches.
+macro_rules! impl_list_formatting {
+  ($ty:ident; $($fmt_trait:ident),+) => {
+    $(
+      impl<A: Array> $fmt_trait for $ty<A>
+      where
+        A::Item: $fmt_trait,
+      {
+        #[allow(clippy::missing_inline_in_public_items)]
+        fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+          write!(f, "[")?;
+          for (i, elem) in self.iter().enumerate() {
+            if i > 0 {
+              write!(f, ", ")?;
+            }
+            $fmt_trait::fmt(elem, f)?;
+          }
+          write!(f, "]")
+        }
+      }
+    )+
+  };
+}
 
 mod array;
 pub use array::*;
@@ -80,6 +123,12 @@ pub use array::*;
 mod arrayvec;
 pub use arrayvec::*;
 
+mod smallarrayvec;
+pub use smallarrayvec::*;
+
+mod veclike;
+pub use veclike::*;
+
 #[cfg(feature = "alloc")]
 mod tinyvec;
 #[cfg(feature = "alloc")]
@@ -0,0 +1,820 @@
+use super::*;
+
+/// An unsigned integer type that a [`SmallArrayVec`] can use for its length.
+///
+/// This is implemented for `u8`, `u16`, `u32`, and `usize`. You are generally
+/// **not** expected to need to implement this yourself.
+pub trait LenType: Copy + Default {
+  /// The largest length that a value of this type can hold.
+  const MAX_LEN: usize;
+
+  /// Converts the length to a `usize`.
+  fn to_usize(self) -> usize;
+
+  /// Converts a `usize` length to this type, if it fits.
+  fn from_usize(len: usize) -> Option<Self>;
+}
+
+macro_rules! impl_len_type {
+  ($($t:ty),*) => {
+    $(
+      impl LenType for $t {
+        // On targets where `usize` is narrower than this type the cast
+        // truncates, which still gives `usize::MAX`.
+        const MAX_LEN: usize = <$t>::max_value() as usize;
+
+        #[inline(always)]
+        #[must_use]
+        fn to_usize(self) -> usize {
+          self as usize
+        }
+
+        #[inline(always)]
+        #[must_use]
+        fn from_usize(len: usize) -> Option<Self> {
+          if len <= Self::MAX_LEN {
+            Some(len as $t)
+          } else {
+            None
+          }
+        }
+      }
+    )*
+  };
+}
+impl_len_type!(u8, u16, u32, usize);
+
+/// An array-backed vector-like data structure with a compact length field.
+///
+/// This is the same as an [`ArrayVec`], except that the length is stored as an
+/// `L` instead of as a `usize`. For small arrays this can shrink the whole vec
+/// considerably: an `ArrayVec<[u8; 8]>` is 16 bytes on a 64-bit target, but a
+/// `SmallArrayVec<[u8; 8], u8>` is only 9 bytes.
+///
+/// You pick the length type yourself, since having it chosen automatically
+/// from the capacity would need an associated type default on [`Array`], which
+/// stable Rust doesn't allow.
+///
+/// * The capacity is the array's capacity or the largest value that `L` can
+///   hold, whichever is smaller. For example, a `SmallArrayVec<[u8; 512], u8>`
+///   can only hold 255 elements.
+/// * Otherwise, the methods behave just like the `ArrayVec` methods of the
+///   same name.
+///
+/// ## Example
+/// ```rust
+/// use tinyvec::*;
+///
+/// let mut sav: SmallArrayVec<[u8; 8], u8> = SmallArrayVec::new();
+/// sav.push(1);
+/// sav.extend_from_slice(&[2, 3]);
+/// assert_eq!(sav.as_slice(), &[1, 2, 3][..]);
+/// assert_eq!(core::mem::size_of_val(&sav), 9);
+/// ```
+#[derive(Clone, Copy)]
+pub struct SmallArrayVec<A, L> {
+  len: L,
+  data: A,
+}
+
+impl<A: Array, L: LenType> Default for SmallArrayVec<A, L> {
+  #[inline]
+  fn default() -> Self {
+    Self { len: L::default(), data: A::default_array() }
+  }
+}
+
+impl<A: Array, L: LenType> Deref for SmallArrayVec<A, L> {
+  type Target = [A::Item];
+  #[inline(always)]
+  #[must_use]
+  fn deref(&self) -> &Self::Target {
+    &self.data.as_slice()[..self.len.to_usize()]
+  }
+}
+
+impl<A: Array, L: LenType> DerefMut for SmallArrayVec<A, L> {
+  #[inline(always)]
+  #[must_use]
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.data.as_slice_mut()[..self.len.to_usize()]
+  }
+}
+
+impl<A: Array, L: LenType, I: SliceIndex<[A::Item]>> Index<I>
+  for SmallArrayVec<A, L>
+{
+  type Output = <I as SliceIndex<[A::Item]>>::Output;
+  #[inline(always)]
+  #[must_use]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  fn index(&self, index: I) -> &Self::Output {
+    &self.deref()[index]
+  }
+}
+
+impl<A: Array, L: LenType, I: SliceIndex<[A::Item]>> IndexMut<I>
+  for SmallArrayVec<A, L>
+{
+  #[inline(always)]
+  #[must_use]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  fn index_mut(&mut self, index: I) -> &mut Self::Output {
+    &mut self.deref_mut()[index]
+  }
+}
+
+impl<A: Array, L: LenType> SmallArrayVec<A, L> {
+  /// Stores a new length, which the caller has already checked against the
+  /// capacity (and so also against `L::MAX_LEN`).
+  #[inline(always)]
+  fn store_len(&mut self, len: usize) {
+    self.len = match L::from_usize(len) {
+      Some(len) => len,
+      None => unreachable!("SmallArrayVec> length {} doesn't fit", len),
+    };
+  }
+
+  /// Move all values from `other` into this vec.
+  ///
+  /// ## Panics
+  /// * If the vec would overflow its capacity. This is checked before anything
+  ///   is moved, so both vecs are left unchanged.
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn append(&mut self, other: &mut Self) {
+    let (len, other_len) = (self.len(), other.len());
+    let new_len = len + other_len;
+    assert!(
+      new_len <= self.capacity(),
+      "SmallArrayVec::append> capacity overflow (len {}, other len {}, capacity {})",
+      len,
+      other_len,
+      self.capacity()
+    );
+    let targets = &mut self.data.as_slice_mut()[len..new_len];
+    for (target, item) in targets.iter_mut().zip(other.as_mut_slice()) {
+      *target = replace(item, A::Item::default());
+    }
+    self.store_len(new_len);
+    other.store_len(0);
+  }
+
+  /// Helper for getting the mut slice.
+  #[inline(always)]
+  #[must_use]
+  pub fn as_mut_slice(&mut self) -> &mut [A::Item] {
+    self.deref_mut()
+  }
+
+  /// Helper for getting the shared slice.
+  #[inline(always)]
+  #[must_use]
+  pub fn as_slice(&self) -> &[A::Item] {
+    self.deref()
+  }
+
+  /// The capacity of the `SmallArrayVec`.
+  ///
+  /// This is the array's capacity, or `L::MAX_LEN` if that's smaller.
+  #[inline(always)]
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    A::CAPACITY.min(L::MAX_LEN)
+  }
+
+  /// Removes all elements from the vec.
+  #[inline(always)]
+  pub fn clear(&mut self) {
+    self.truncate(0)
+  }
+
+  /// Creates a draining iterator that removes the specified range in the vector
+  /// and yields the removed items.
+  ///
+  /// ## Panics
+  /// * If the start is greater than the end
+  /// * If the end is past the edge of the vec.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut sav: SmallArrayVec<[i32; 4], u8> = SmallArrayVec::new();
+  /// sav.extend_from_slice(&[1, 2, 3]);
+  /// let drained: Vec<i32> = sav.drain(1..).collect();
+  /// assert_eq!(sav.as_slice(), &[1][..]);
+  /// assert_eq!(drained, vec![2, 3]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn drain<R: RangeBounds<usize>>(
+    &mut self,
+    range: R,
+  ) -> SmallArrayVecDrain<'_, A, L> {
+    use core::ops::Bound;
+    let len = self.len();
+    let start = match range.start_bound() {
+      Bound::Included(x) => *x,
+      Bound::Excluded(x) => x + 1,
+      Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+      Bound::Included(x) => x + 1,
+      Bound::Excluded(x) => *x,
+      Bound::Unbounded => len,
+    };
+    assert!(
+      start <= end,
+      "SmallArrayVec::drain> illegal range {} to {} (len {}, capacity {})",
+      start,
+      end,
+      len,
+      self.capacity()
+    );
+    assert!(
+      end <= len,
+      "SmallArrayVec::drain> range end {} is out of bounds (len {}, capacity {})",
+      end,
+      len,
+      self.capacity()
+    );
+    self.store_len(start);
+    SmallArrayVecDrain {
+      parent: self,
+      target_start: start,
+      target_index: start,
+      target_end: end,
+      range_end: end,
+      old_len: len,
+    }
+  }
+
+  /// Clone each element of the slice into this vec.
+  ///
+  /// ## Panics
+  /// * If the slice doesn't fit in the vec's spare capacity. This is checked
+  ///   before anything is cloned, so the vec is left unchanged.
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn extend_from_slice(&mut self, sli: &[A::Item])
+  where
+    A::Item: Clone,
+  {
+    assert!(
+      sli.len() <= self.capacity() - self.len(),
+      "SmallArrayVec::extend_from_slice> {} items don't fit (len {}, capacity {})",
+      sli.len(),
+      self.len(),
+      self.capacity()
+    );
+    for i in sli {
+      self.push(i.clone())
+    }
+  }
+
+  /// Wraps up an array and uses the given length as the initial length.
+  ///
+  /// ## Panics
+  ///
+  /// The length must be less than or equal to the capacity of the vec.
+  #[inline]
+  #[must_use]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn from_array_len(data: A, len: usize) -> Self {
+    match Self::try_from_array_len(data, len) {
+      Ok(out) => out,
+      Err(_) => panic!(
+        "SmallArrayVec::from_array_len> length {} exceeds capacity {}",
+        len,
+        A::CAPACITY.min(L::MAX_LEN)
+      ),
+    }
+  }
+
+  /// Inserts an item at the position given, moving all following elements +1
+  /// index.
+  ///
+  /// ## Panics
+  /// * If `index` > `len`
+  /// * If the vec is already at capacity (the vec is left unchanged)
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn insert(&mut self, index: usize, item: A::Item) {
+    let len = self.len();
+    assert!(
+      index <= len,
+      "SmallArrayVec::insert> index {} is out of bounds (len {}, capacity {})",
+      index,
+      len,
+      self.capacity()
+    );
+    assert!(
+      len < self.capacity(),
+      "SmallArrayVec::insert> capacity overflow (len {}, capacity {})",
+      len,
+      self.capacity()
+    );
+    let targets = &mut self.data.as_slice_mut()[index..=len];
+    targets.rotate_right(1);
+    targets[0] = item;
+    self.store_len(len + 1);
+  }
+
+  /// Moves the contents into an `ArrayVec` with a full `usize` length.
+  #[inline]
+  #[must_use]
+  pub fn into_array_vec(self) -> ArrayVec<A> {
+    let len = self.len();
+    ArrayVec::from_array_len(self.data, len)
+  }
+
+  /// If the vec is empty.
+  #[inline(always)]
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// The length of the vec (in elements).
+  #[inline(always)]
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.len.to_usize()
+  }
+
+  /// Makes a new, empty vec.
+  #[inline(always)]
+  #[must_use]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Remove and return the last element of the vec, if there is one.
+  ///
+  /// ## Failure
+  /// * If the vec is empty you get `None`.
+  #[inline]
+  pub fn pop(&mut self) -> Option<A::Item> {
+    let len = self.len();
+    if len > 0 {
+      self.store_len(len - 1);
+      let out =
+        replace(&mut self.data.as_slice_mut()[len - 1], A::Item::default());
+      Some(out)
+    } else {
+      None
+    }
+  }
+
+  /// Place an element onto the end of the vec.
+  ///
+  /// ## Panics
+  /// * If the length of the vec would overflow the capacity.
+  #[inline(always)]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn push(&mut self, val: A::Item) {
+    let len = self.len();
+    if len < self.capacity() {
+      self.data.as_slice_mut()[len] = val;
+      self.store_len(len + 1);
+    } else {
+      panic!(
+        "SmallArrayVec::push> capacity overflow (len {}, capacity {})",
+        len,
+        self.capacity()
+      )
+    }
+  }
+
+  /// Removes the item at `index`, shifting all others down by one index.
+  ///
+  /// Returns the removed element.
+  ///
+  /// ## Panics
+  ///
+  /// If the index is out of bounds.
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn remove(&mut self, index: usize) -> A::Item {
+    let len = self.len();
+    assert!(
+      index < len,
+      "SmallArrayVec::remove> index {} is out of bounds (len {}, capacity {})",
+      index,
+      len,
+      self.capacity()
+    );
+    let targets = &mut self.deref_mut()[index..];
+    let out = replace(&mut targets[0], A::Item::default());
+    targets.rotate_left(1);
+    self.store_len(len - 1);
+    out
+  }
+
+  /// Resize the vec to the new length.
+  ///
+  /// If it needs to be longer, it's filled with clones of the provided value.
+  /// If it needs to be shorter, it's truncated.
+  ///
+  /// ## Panics
+  /// * If `new_len` is more than the capacity.
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn resize(&mut self, new_len: usize, new_val: A::Item)
+  where
+    A::Item: Clone,
+  {
+    if new_len <= self.len() {
+      self.truncate(new_len)
+    } else {
+      while self.len() + 1 < new_len {
+        self.push(new_val.clone());
+      }
+      self.push(new_val);
+    }
+  }
+
+  /// Resize the vec to the new length.
+  ///
+  /// If it needs to be longer, it's filled with repeated calls to the provided
+  /// function. If it needs to be shorter, it's truncated.
+  ///
+  /// ## Panics
+  /// * If `new_len` is more than the capacity.
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn resize_with<F: FnMut() -> A::Item>(
+    &mut self,
+    new_len: usize,
+    mut f: F,
+  ) {
+    if new_len < self.len() {
+      self.truncate(new_len)
+    } else {
+      while self.len() < new_len {
+        self.push(f());
+      }
+    }
+  }
+
+  /// Walk the vec and keep only the elements that pass the predicate given.
+  #[inline]
+  pub fn retain<F: FnMut(&A::Item) -> bool>(&mut self, mut acceptable: F) {
+    let len = self.len();
+    let mut guard = RetainGuard { sav: self, len, kept: 0, processed: 0 };
+    while guard.processed < len {
+      let i = guard.processed;
+      let targets = guard.sav.data.as_slice_mut();
+      if acceptable(&targets[i]) {
+        targets.swap(guard.kept, i);
+        guard.kept += 1;
+      } else {
+        targets[i] = A::Item::default();
+      }
+      guard.processed += 1;
+    }
+  }
+
+  /// Forces the length of the vector to `new_len`.
+  ///
+  /// ## Panics
+  /// If `new_len` is greater than the vec's capacity.
+  ///
+  /// ## Safety
+  /// * This is a fully safe operation! The inactive memory already counts as
+  ///   "initialized" by Rust's rules.
+  #[inline(always)]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn set_len(&mut self, new_len: usize) {
+    if new_len > self.capacity() {
+      panic!(
+        "SmallArrayVec::set_len> length {} exceeds capacity (len {}, capacity {})",
+        new_len,
+        self.len(),
+        self.capacity()
+      )
+    } else {
+      self.store_len(new_len);
+    }
+  }
+
+  /// Splits the collection at the point given.
+  ///
+  /// * `[0, at)` stays in this vec
+  /// * `[at, len)` ends up in the new vec.
+  ///
+  /// ## Panics
+  /// * if at > len
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn split_off(&mut self, at: usize) -> Self {
+    let len = self.len();
+    if at > len {
+      panic!(
+        "SmallArrayVec::split_off> index {} is out of bounds (len {}, capacity {})",
+        at,
+        len,
+        self.capacity()
+      );
+    }
+    let mut new = Self::default();
+    let moves = &mut self.as_mut_slice()[at..];
+    moves.swap_with_slice(&mut new.data.as_slice_mut()[..moves.len()]);
+    new.store_len(len - at);
+    self.store_len(at);
+    new
+  }
+
+  /// Remove an element, swapping the end of the vec into its place.
+  ///
+  /// ## Panics
+  /// * If the index is out of bounds.
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn swap_remove(&mut self, index: usize) -> A::Item {
+    let len = self.len();
+    assert!(
+      index < len,
+      "SmallArrayVec::swap_remove> index {} is out of bounds (len {}, capacity {})",
+      index,
+      len,
+      self.capacity()
+    );
+    self.as_mut_slice().swap(index, len - 1);
+    self.pop().unwrap()
+  }
+
+  /// Reduces the vec's length to the given value.
+  ///
+  /// If the vec is already shorter than the input, nothing happens.
+  #[inline]
+  pub fn truncate(&mut self, new_len: usize) {
+    let old_len = self.len();
+    if new_len >= old_len {
+      return;
+    }
+    self.store_len(new_len);
+    if needs_drop::<A::Item>() {
+      for doomed in &mut self.data.as_slice_mut()[new_len..old_len] {
+        *doomed = A::Item::default();
+      }
+    }
+  }
+
+  /// Wraps an array, using the given length as the starting length.
+  ///
+  /// ## Failure
+  ///
+  /// If the given length is greater than the capacity of the vec this will
+  /// error, and you'll get the array back in the `Err`.
+  #[inline]
+  pub fn try_from_array_len(data: A, len: usize) -> Result<Self, A> {
+    if len > A::CAPACITY {
+      return Err(data);
+    }
+    match L::from_usize(len) {
+      Some(len) => Ok(Self { len, data }),
+      None => Err(data),
+    }
+  }
+}
+
+/// Finishes a `retain` pass when dropped, even if the predicate panicked.
+struct RetainGuard<'p, A: Array, L: LenType> {
+  sav: &'p mut SmallArrayVec<A, L>,
+  len: usize,
+  kept: usize,
+  processed: usize,
+}
+impl<'p, A: Array, L: LenType> Drop for RetainGuard<'p, A, L> {
+  #[inline]
+  fn drop(&mut self) {
+    if self.processed < self.len {
+      let unprocessed = &mut self.sav.data.as_slice_mut()[self.kept..self.len];
+      unprocessed.rotate_left(self.processed - self.kept);
+    }
+    self.sav.store_len(self.kept + (self.len - self.processed));
+  }
+}
+
+/// Draining iterator for `SmallArrayVec`
+///
+/// See [`SmallArrayVec::drain`](SmallArrayVec::<A, L>::drain)
+///
+/// This works just like [`ArrayVecDrain`]: items are taken out of the range in
+/// place, and the tail is moved down once when the drain is dropped.
+pub struct SmallArrayVecDrain<'p, A: Array, L: LenType> {
+  parent: &'p mut SmallArrayVec<A, L>,
+  /// Start of the drained range.
+  target_start: usize,
+  /// The next item to yield from the front.
+  target_index: usize,
+  /// One past the next item to yield from the back.
+  target_end: usize,
+  /// End of the drained range.
+  range_end: usize,
+  /// The parent's length before the drain.
+  old_len: usize,
+}
+impl<'p, A: Array, L: LenType> Iterator for SmallArrayVecDrain<'p, A, L> {
+  type Item = A::Item;
+  #[inline]
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.target_index < self.target_end {
+      let slot = &mut self.parent.data.as_slice_mut()[self.target_index];
+      self.target_index += 1;
+      Some(replace(slot, A::Item::default()))
+    } else {
+      None
+    }
+  }
+  #[inline(always)]
+  #[must_use]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let s = self.target_end - self.target_index;
+    (s, Some(s))
+  }
+}
+impl<'p, A: Array, L: LenType> DoubleEndedIterator
+  for SmallArrayVecDrain<'p, A, L>
+{
+  #[inline]
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.target_index < self.target_end {
+      self.target_end -= 1;
+      let slot = &mut self.parent.data.as_slice_mut()[self.target_end];
+      Some(replace(slot, A::Item::default()))
+    } else {
+      None
+    }
+  }
+}
+impl<'p, A: Array, L: LenType> ExactSizeIterator
+  for SmallArrayVecDrain<'p, A, L>
+{
+}
+impl<'p, A: Array, L: LenType> Drop for SmallArrayVecDrain<'p, A, L> {
+  #[inline]
+  fn drop(&mut self) {
+    let data = self.parent.data.as_slice_mut();
+    if needs_drop::<A::Item>() {
+      for unyielded in &mut data[self.target_index..self.target_end] {
+        *unyielded = A::Item::default();
+      }
+    }
+    let removed = self.range_end - self.target_start;
+    data[self.target_start..self.old_len].rotate_left(removed);
+    self.parent.store_len(self.old_len - removed);
+  }
+}
+
+impl<A: Array, L: LenType> AsMut<[A::Item]> for SmallArrayVec<A, L> {
+  #[inline(always)]
+  #[must_use]
+  fn as_mut(&mut self) -> &mut [A::Item] {
+    &mut *self
+  }
+}
+
+impl<A: Array, L: LenType> AsRef<[A::Item]> for SmallArrayVec<A, L> {
+  #[inline(always)]
+  #[must_use]
+  fn as_ref(&self) -> &[A::Item] {
+    &*self
+  }
+}
+
+impl<A: Array, L: LenType> Borrow<[A::Item]> for SmallArrayVec<A, L> {
+  #[inline(always)]
+  #[must_use]
+  fn borrow(&self) -> &[A::Item] {
+    &*self
+  }
+}
+
+impl<A: Array, L: LenType> BorrowMut<[A::Item]> for SmallArrayVec<A, L> {
+  #[inline(always)]
+  #[must_use]
+  fn borrow_mut(&mut self) -> &mut [A::Item] {
+    &mut *self
+  }
+}
+
+impl<A: Array, L: LenType> Extend<A::Item> for SmallArrayVec<A, L> {
+  /// ## Panics
+  /// * If the iterator yields more items than fit in the vec. When the
+  ///   iterator's `size_hint` already says that it won't fit this happens
+  ///   before anything is taken from the iterator, and the vec is unchanged.
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  fn extend<T: IntoIterator<Item = A::Item>>(&mut self, iter: T) {
+    let iter = iter.into_iter();
+    let (lower, _) = iter.size_hint();
+    assert!(
+      lower <= self.capacity() - self.len(),
+      "SmallArrayVec::extend> at least {} items don't fit (len {}, capacity {})",
+      lower,
+      self.len(),
+      self.capacity()
+    );
+    for t in iter {
+      self.push(t)
+    }
+  }
+}
+
+impl<A: Array, L: LenType> From<A> for SmallArrayVec<A, L> {
+  #[inline(always)]
+  #[must_use]
+  /// The output has a length equal to the full array.
+  ///
+  /// ## Panics
+  /// * If the array's length doesn't fit in `L`.
+  fn from(data: A) -> Self {
+    let len = data.as_slice().len();
+    Self::from_array_len(data, len)
+  }
+}
+
+impl<A: Array, L: LenType> From<SmallArrayVec<A, L>> for ArrayVec<A> {
+  #[inline(always)]
+  #[must_use]
+  fn from(sav: SmallArrayVec<A, L>) -> Self {
+    sav.into_array_vec()
+  }
+}
+
+impl<A: Array, L: LenType> FromIterator<A::Item> for SmallArrayVec<A, L> {
+  #[inline]
+  #[must_use]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  fn from_iter<T: IntoIterator<Item = A::Item>>(iter: T) -> Self {
+    let mut sav = Self::default();
+    sav.extend(iter);
+    sav
+  }
+}
+
+impl<A: Array, L: LenType> IntoIterator for SmallArrayVec<A, L> {
+  type Item = A::Item;
+  type IntoIter = ArrayVecIterator<A>;
+  #[inline(always)]
+  #[must_use]
+  fn into_iter(self) -> Self::IntoIter {
+    self.into_array_vec().into_iter()
+  }
+}
+
+impl<A: Array, L: LenType> PartialEq for SmallArrayVec<A, L>
+where
+  A::Item: PartialEq,
+{
+  #[inline]
+  #[must_use]
+  fn eq(&self, other: &Self) -> bool {
+    self.deref().eq(other.deref())
+  }
+}
+impl<A: Array, L: LenType> Eq for SmallArrayVec<A, L> where A::Item: Eq {}
+
+impl<A: Array, L: LenType> PartialOrd for SmallArrayVec<A, L>
+where
+  A::Item: PartialOrd,
+{
+  #[inline]
+  #[must_use]
+  fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+    self.deref().partial_cmp(other.deref())
+  }
+}
+impl<A: Array, L: LenType> Ord for SmallArrayVec<A, L>
+where
+  A::Item: Ord,
+{
+  #[inline]
+  #[must_use]
+  fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+    self.deref().cmp(other.deref())
+  }
+}
+
+impl<A: Array, L: LenType> PartialEq<&[A::Item]> for SmallArrayVec<A, L>
+where
+  A::Item: PartialEq,
+{
+  #[inline]
+  #[must_use]
+  fn eq(&self, other: &&[A::Item]) -> bool {
+    self.deref() == *other
+  }
+}
+
+impl<A: Array, L: LenType> Debug for SmallArrayVec<A, L>
+where
+  A::Item: Debug,
+{
+  #[allow(clippy::missing_inline_in_public_items)]
+  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+    Debug::fmt(self.deref(), f)
+  }
+}
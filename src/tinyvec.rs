@@ -2,38 +2,89 @@
 
 use super::*;
 
-use alloc::vec::Vec;
+use alloc::{boxed::Box, vec::Vec};
+use core::hash::{Hash, Hasher};
+
+#[cfg(feature = "rustc_1_57")]
+pub use alloc::collections::TryReserveError;
 
 /// Helper to make a `TinyVec`.
 ///
 /// You specify the backing array type, and optionally give all the elements you
 /// want to initially place into the array.
 ///
-/// As an unfortunate restriction, the backing array type must support `Default`
-/// for it to work with this macro.
-///
 /// ```rust
 /// use tinyvec::*;
-/// 
+///
 /// let empty_av = tiny_vec!([u8; 16]);
-/// 
+///
 /// let some_ints = tiny_vec!([i32; 4], 1, 2, 3);
 /// ```
+///
+/// This accepts all the same forms as [`array_vec!`](array_vec!), but if there
+/// are more elements than the array can hold the vec just starts out on the
+/// heap instead of panicking.
+///
+/// ```rust
+/// use tinyvec::*;
+///
+/// let some_ints = tiny_vec!(i32; 4 => 1, 2, 3);
+/// assert_eq!(some_ints.as_slice(), &[1, 2, 3][..]);
+///
+/// let many_ints = tiny_vec!([i32; 2], 1, 2, 3);
+/// match many_ints {
+///   TinyVec::Heap(v) => assert_eq!(v, vec![1, 2, 3]),
+///   TinyVec::Inline(_) => unreachable!(),
+/// }
+///
+/// let zeros = tiny_vec!([u8; 16] => 0; 100);
+/// assert_eq!(zeros.len(), 100);
+/// ```
 #[macro_export]
 macro_rules! tiny_vec {
   ($array_type:ty) => {
     {
-      let mut tv: TinyVec<$array_type> = Default::default();
+      let tv: $crate::TinyVec<$array_type> = Default::default();
       tv
     }
   };
   ($array_type:ty, $($elem:expr),*) => {
     {
-      let mut tv: TinyVec<$array_type> = Default::default();
-      $( tv.push($elem); )*
-      tv
+      let count: usize = 0 $( + { let _ = stringify!($elem); 1 } )*;
+      // Both arms are built from the element list directly, so there's no
+      // per-element push (or reallocation) either way.
+      if count <= <$array_type as $crate::Array>::CAPACITY {
+        $crate::TinyVec::<$array_type>::Inline(
+          $crate::array_vec!(@fill $array_type, $($elem),*)
+        )
+      } else {
+        $crate::TinyVec::<$array_type>::Heap(
+          $crate::alloc::vec![$($elem),*]
+        )
+      }
+    }
+  };
+  ($array_type:ty => $elem:expr; $count:expr) => {
+    {
+      let count: usize = $count;
+      let elem = $elem;
+      if count <= <$array_type as $crate::Array>::CAPACITY {
+        $crate::TinyVec::<$array_type>::Inline(
+          $crate::array_vec!($array_type => elem; count)
+        )
+      } else {
+        $crate::TinyVec::<$array_type>::Heap(
+          $crate::alloc::vec![elem; count]
+        )
+      }
     }
   };
+  ($item_type:ty; $capacity:expr) => {
+    $crate::tiny_vec!([$item_type; $capacity])
+  };
+  ($item_type:ty; $capacity:expr => $($elem:expr),*) => {
+    $crate::tiny_vec!([$item_type; $capacity], $($elem),*)
+  };
 }
 
 /// A vector that starts inline, but can automatically move to the heap.
@@ -46,33 +97,131 @@ macro_rules! tiny_vec {
 /// internals" since no safety invariants are on the line. It's kinda wild how
 /// much you can just let people poke at stuff without worry when it's 100% safe
 /// code.
-#[derive(Clone)]
 pub enum TinyVec<A: Array> {
   #[allow(missing_docs)]
   Inline(ArrayVec<A>),
   #[allow(missing_docs)]
   Heap(Vec<A::Item>)
 }
-impl<A: Array + Default> Default for TinyVec<A> {
+impl<A: Array> Default for TinyVec<A> {
   #[inline]
   #[must_use]
   fn default() -> Self {
     TinyVec::Inline(ArrayVec::default())
   }
 }
+
+impl<A: Array> Clone for TinyVec<A>
+where
+  A::Item: Clone,
+{
+  /// Only the active elements are cloned, the spare part of an inline vec is
+  /// left as defaults.
+  #[inline]
+  fn clone(&self) -> Self {
+    match self {
+      TinyVec::Inline(a) => {
+        let mut new = ArrayVec::new();
+        new.extend_from_slice(a);
+        TinyVec::Inline(new)
+      }
+      TinyVec::Heap(v) => TinyVec::Heap(v.clone()),
+    }
+  }
+
+  /// Reuses this vec's storage where it can.
+  ///
+  /// The elements both vecs have are updated with `clone_from`, and then the
+  /// rest are cloned or truncated away. A heap vec keeps its allocation, and an
+  /// inline vec only moves to the heap if `source` doesn't fit inline.
+  #[inline]
+  fn clone_from(&mut self, source: &Self) {
+    self.truncate(source.len());
+    let shared = self.len();
+    self.reserve(source.len() - shared);
+    self.as_mut_slice().clone_from_slice(&source[..shared]);
+    self.extend_from_slice(&source[shared..]);
+  }
+}
 impl<A: Array> TinyVec<A> {
   /// Moves the content of the TinyVec to the heap, if it's inline.
+  ///
+  /// The elements are moved, not cloned, and the new allocation has room for
+  /// another inline capacity's worth of elements.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([i32; 4], 1, 2, 3);
+  /// tv.move_to_the_heap();
+  /// assert_eq!(tv.as_slice(), &[1, 2, 3][..]);
+  /// assert!(tv.is_heap());
+  /// ```
   #[allow(clippy::missing_inline_in_public_items)]
   pub fn move_to_the_heap(&mut self) {
+    match self {
+      TinyVec::Inline(_) => self.move_to_the_heap_and_reserve(A::CAPACITY),
+      TinyVec::Heap(_) => (),
+    }
+  }
+
+  /// Moves the content of the TinyVec to the heap (if it's inline), making
+  /// room for at least `n` more elements.
+  ///
+  /// An inline vec gets a single allocation of `len + n` elements. A heap vec
+  /// just reserves, like `Vec::reserve`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([i32; 4], 1, 2, 3);
+  /// tv.move_to_the_heap_and_reserve(100);
+  /// assert_eq!(tv.as_slice(), &[1, 2, 3][..]);
+  /// assert!(tv.is_heap());
+  /// assert!(tv.capacity() >= 103);
+  /// ```
+  #[allow(clippy::missing_inline_in_public_items)]
+  pub fn move_to_the_heap_and_reserve(&mut self, n: usize) {
     match self {
       TinyVec::Inline(ref mut arr) => {
-        let mut v = Vec::with_capacity(A::CAPACITY * 2);
-        for item in arr.drain(..) {
-          v.push(item);
-        }
-        replace(self, TinyVec::Heap(v));
+        let mut v = Vec::with_capacity(arr.len() + n);
+        v.extend(arr.drain(..));
+        *self = TinyVec::Heap(v);
       }
-      TinyVec::Heap(_) => (),
+      TinyVec::Heap(v) => v.reserve(n),
+    }
+  }
+
+  /// Like [`move_to_the_heap`](TinyVec::<A>::move_to_the_heap), but also
+  /// zeroizes the whole inline array before the vec switches over to the heap.
+  ///
+  /// A normal spill leaves `Default` values behind in the inline memory, which
+  /// for most types isn't the same as scrubbing it. If the vec will hold
+  /// secrets, spill it with this method before it would spill on its own.
+  /// Copies that the compiler makes while moving the elements (e.g. on the
+  /// stack) can't be reached from here.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # #[cfg(feature = "zeroize")] {
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([u8; 4], 1, 2, 3);
+  /// tv.zeroize_spill();
+  /// assert!(tv.is_heap());
+  /// assert_eq!(tv.as_slice(), &[1, 2, 3][..]);
+  /// # }
+  /// ```
+  #[cfg(feature = "zeroize")]
+  #[allow(clippy::missing_inline_in_public_items)]
+  pub fn zeroize_spill(&mut self)
+  where
+    A::Item: zeroize::Zeroize,
+  {
+    if let TinyVec::Inline(arr) = self {
+      let mut v = Vec::with_capacity(arr.len() + A::CAPACITY);
+      v.extend(arr.drain(..));
+      zeroize::Zeroize::zeroize(arr);
+      *self = TinyVec::Heap(v);
     }
   }
 }
@@ -119,10 +268,97 @@ impl<A: Array, I: SliceIndex<[A::Item]>> IndexMut<I> for TinyVec<A> {
 
 impl<A: Array> TinyVec<A> {
   /// Move all values from `other` into this vec.
+  ///
+  /// `other` is left empty, but keeps any heap allocation it had. If the
+  /// elements don't fit inline this vec moves to the heap first, reserving room
+  /// for all of them at once.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([i32; 4], 1, 2, 3);
+  /// let mut tv2 = tiny_vec!([i32; 4], 4, 5);
+  /// tv.append(&mut tv2);
+  /// assert_eq!(tv.as_slice(), &[1, 2, 3, 4, 5][..]);
+  /// assert!(tv2.is_empty());
+  /// ```
   #[inline]
   pub fn append(&mut self, other: &mut Self) {
-    for item in other.drain(..) {
-      self.push(item)
+    let n = other.len();
+    if let TinyVec::Inline(a) = self {
+      if a.len() + n > A::CAPACITY {
+        self.move_to_the_heap_and_reserve(n);
+      }
+    }
+    match (self, other) {
+      (TinyVec::Inline(a), TinyVec::Inline(b)) => a.append(b),
+      (TinyVec::Inline(a), TinyVec::Heap(b)) => a.extend(b.drain(..)),
+      (TinyVec::Heap(a), TinyVec::Inline(b)) => a.extend(b.drain(..)),
+      (TinyVec::Heap(a), TinyVec::Heap(b)) => a.append(b),
+    }
+  }
+
+  /// The `Vec`, if the vec is on the heap.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([i32; 2], 1, 2);
+  /// assert!(tv.as_heap().is_none());
+  /// tv.push(3);
+  /// assert_eq!(tv.as_heap().unwrap().as_slice(), &[1, 2, 3][..]);
+  /// ```
+  #[inline(always)]
+  #[must_use]
+  pub fn as_heap(&self) -> Option<&Vec<A::Item>> {
+    match self {
+      TinyVec::Inline(_) => None,
+      TinyVec::Heap(v) => Some(v),
+    }
+  }
+
+  /// The `Vec`, mutably, if the vec is on the heap.
+  ///
+  /// Anything you can do to a `Vec` is fine here, including changing its
+  /// length or replacing it entirely. The vec stays on the heap either way,
+  /// even if it ends up short enough to fit inline.
+  #[inline(always)]
+  #[must_use]
+  pub fn as_heap_mut(&mut self) -> Option<&mut Vec<A::Item>> {
+    match self {
+      TinyVec::Inline(_) => None,
+      TinyVec::Heap(v) => Some(v),
+    }
+  }
+
+  /// The `ArrayVec`, if the vec is inline.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let tv = tiny_vec!([i32; 2], 1, 2);
+  /// assert_eq!(tv.as_inline().unwrap().capacity(), 2);
+  /// ```
+  #[inline(always)]
+  #[must_use]
+  pub fn as_inline(&self) -> Option<&ArrayVec<A>> {
+    match self {
+      TinyVec::Inline(a) => Some(a),
+      TinyVec::Heap(_) => None,
+    }
+  }
+
+  /// The `ArrayVec`, mutably, if the vec is inline.
+  ///
+  /// As with [`as_heap_mut`](TinyVec::<A>::as_heap_mut), any change to it is
+  /// fine. Note that the `ArrayVec` can't move the vec to the heap itself, so
+  /// pushing past its capacity panics like it normally would.
+  #[inline(always)]
+  #[must_use]
+  pub fn as_inline_mut(&mut self) -> Option<&mut ArrayVec<A>> {
+    match self {
+      TinyVec::Inline(a) => Some(a),
+      TinyVec::Heap(_) => None,
     }
   }
 
@@ -169,23 +405,60 @@ impl<A: Array> TinyVec<A> {
   }
 
   /// The capacity of the `TinyVec`.
-  /// 
-  /// This is fixed based on the array type.
+  ///
+  /// When inline this is the inline capacity, which is fixed based on the
+  /// array type. When on the heap it's the capacity of the `Vec`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([i32; 4], 1, 2, 3);
+  /// assert_eq!(tv.capacity(), 4);
+  /// tv.move_to_the_heap_and_reserve(10);
+  /// assert!(tv.capacity() >= 13);
+  /// ```
   #[inline(always)]
   #[must_use]
   pub fn capacity(&self) -> usize {
-    A::CAPACITY
+    match self {
+      TinyVec::Inline(_) => A::CAPACITY,
+      TinyVec::Heap(v) => v.capacity(),
+    }
   }
 
   /// Removes all elements from the vec.
+  ///
+  /// Like [`truncate`](TinyVec::<A>::truncate), this keeps the variant and
+  /// the capacity, so a cleared heap vec can be refilled without allocating.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv: TinyVec<[i32; 2]> = (1..=5).collect();
+  /// let cap = tv.capacity();
+  /// tv.clear();
+  /// assert!(tv.is_empty());
+  /// assert!(tv.is_heap());
+  /// assert_eq!(tv.capacity(), cap);
+  /// ```
   #[inline(always)]
   pub fn clear(&mut self) {
     self.truncate(0)
   }
-  
+
   /// De-duplicates the vec.
-  #[cfg(feature = "nightly_slice_partition_dedup")]
-  #[inline(always)]
+  ///
+  /// Consecutive equal elements are removed (and dropped), keeping the first
+  /// of each run. The vec keeps its variant.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([i32; 8], 1, 1, 2, 3, 3, 3, 1);
+  /// tv.dedup();
+  /// assert_eq!(tv.as_slice(), &[1, 2, 3, 1][..]);
+  /// ```
+  #[inline]
   pub fn dedup(&mut self)
   where
     A::Item: PartialEq,
@@ -194,22 +467,20 @@ impl<A: Array> TinyVec<A> {
   }
 
   /// De-duplicates the vec according to the predicate given.
-  #[cfg(feature = "nightly_slice_partition_dedup")]
-  #[inline(always)]
+  ///
+  /// The predicate gets `(current, previous_kept)`, and the current element is
+  /// removed if it returns `true`.
+  #[inline]
   pub fn dedup_by<F>(&mut self, same_bucket: F)
   where
     F: FnMut(&mut A::Item, &mut A::Item) -> bool,
   {
-    let len = {
-      let (dedup, _) = self.as_mut_slice().partition_dedup_by(same_bucket);
-      dedup.len()
-    };
+    let len = dedup_partition(self.as_mut_slice(), same_bucket);
     self.truncate(len);
   }
 
   /// De-duplicates the vec according to the key selector given.
-  #[cfg(feature = "nightly_slice_partition_dedup")]
-  #[inline(always)]
+  #[inline]
   pub fn dedup_by_key<F, K>(&mut self, mut key: F)
   where
     F: FnMut(&mut A::Item) -> K,
@@ -237,6 +508,7 @@ impl<A: Array> TinyVec<A> {
   /// assert_eq!(tv.as_slice(), &[]);
   /// ```
   #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
   pub fn drain<R: RangeBounds<usize>>(
     &mut self,
     range: R,
@@ -254,33 +526,109 @@ impl<A: Array> TinyVec<A> {
     };
     assert!(
       start <= end,
-      "TinyVec::drain> Illegal range, {} to {}",
+      "TinyVec::drain> illegal range {} to {} (len {})",
       start,
-      end
+      end,
+      self.len()
     );
     assert!(
       end <= self.len(),
-      "TinyVec::drain> Range ends at {} but length is only {}!",
+      "TinyVec::drain> range end {} is out of bounds (len {})",
       end,
       self.len()
     );
-    TinyVecDrain {
-      parent: self,
-      target_index: start,
-      target_count: end - start,
+    match self {
+      TinyVec::Inline(a) => TinyVecDrain::Inline(a.drain(start..end)),
+      TinyVec::Heap(v) => TinyVecDrain::Heap(v.drain(start..end)),
     }
   }
 
   // LATER(Vec): drain_filter #nightly https://github.com/rust-lang/rust/issues/43244
 
   /// Clone each element of the slice into this vec.
+  ///
+  /// If the elements don't fit inline this moves to the heap first, allocating
+  /// the full final length at once.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([i32; 4], 1, 2);
+  /// tv.extend_from_slice(&[3, 4, 5]);
+  /// assert_eq!(tv.as_slice(), &[1, 2, 3, 4, 5][..]);
+  /// ```
   #[inline]
   pub fn extend_from_slice(&mut self, sli: &[A::Item])
   where
     A::Item: Clone,
   {
-    for i in sli {
-      self.push(i.clone())
+    match self {
+      TinyVec::Inline(a) if sli.len() <= A::CAPACITY - a.len() => {
+        a.extend_from_slice(sli)
+      }
+      TinyVec::Inline(_) => {
+        self.move_to_the_heap_and_reserve(sli.len());
+        self.extend_from_slice(sli)
+      }
+      TinyVec::Heap(v) => v.extend_from_slice(sli),
+    }
+  }
+
+  /// Clones the elements in the range `src` onto the end of the vec.
+  ///
+  /// If they don't fit inline this moves to the heap first. The range always
+  /// refers to the elements as they were before the call.
+  ///
+  /// ## Panics
+  /// * If the start of the range is greater than the end.
+  /// * If the end of the range is past the edge of the vec.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([u8; 4], b'a', b'b');
+  /// tv.extend_from_within(..);
+  /// tv.extend_from_within(1..3);
+  /// assert_eq!(tv.as_slice(), b"ababba");
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn extend_from_within<R: RangeBounds<usize>>(&mut self, src: R)
+  where
+    A::Item: Clone,
+  {
+    use core::ops::Bound;
+    let start = match src.start_bound() {
+      Bound::Included(x) => *x,
+      Bound::Excluded(x) => x + 1,
+      Bound::Unbounded => 0,
+    };
+    let end = match src.end_bound() {
+      Bound::Included(x) => x + 1,
+      Bound::Excluded(x) => *x,
+      Bound::Unbounded => self.len(),
+    };
+    assert!(
+      start <= end,
+      "TinyVec::extend_from_within> illegal range {} to {} (len {})",
+      start,
+      end,
+      self.len()
+    );
+    assert!(
+      end <= self.len(),
+      "TinyVec::extend_from_within> range end {} is out of bounds (len {})",
+      end,
+      self.len()
+    );
+    let n = end - start;
+    match self {
+      TinyVec::Inline(a) if n <= A::CAPACITY - a.len() => (),
+      _ => self.move_to_the_heap_and_reserve(n),
+    }
+    for i in start..end {
+      let item = self[i].clone();
+      self.push(item);
     }
   }
 
@@ -303,6 +651,26 @@ impl<A: Array> TinyVec<A> {
     }
   }
 
+  /// Wraps a `Vec`, without moving or copying its elements.
+  ///
+  /// The result is always on the heap, even if the elements would fit inline.
+  /// Use [`shrink_to_fit`](TinyVec::<A>::shrink_to_fit) or
+  /// [`try_move_to_inline`](TinyVec::<A>::try_move_to_inline) afterwards if
+  /// you'd rather have them inline.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let tv: TinyVec<[i32; 4]> = TinyVec::from_vec(vec![1, 2, 3]);
+  /// assert!(tv.is_heap());
+  /// assert_eq!(tv.as_slice(), &[1, 2, 3][..]);
+  /// ```
+  #[inline(always)]
+  #[must_use]
+  pub fn from_vec(v: Vec<A::Item>) -> Self {
+    TinyVec::Heap(v)
+  }
+
   /// Inserts an item at the position given, moving all following elements +1
   /// index.
   ///
@@ -318,19 +686,110 @@ impl<A: Array> TinyVec<A> {
   /// tv.insert(4, 5);
   /// assert_eq!(tv.as_slice(), &[1, 4, 2, 3, 5]);
   /// ```
+  ///
+  /// If the vec is inline and full this moves everything to the heap first,
+  /// placing the new element in the same pass.
   #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
   pub fn insert(&mut self, index: usize, item: A::Item) {
     match self {
-      TinyVec::Inline(a) => if a.len() == A::CAPACITY {
-        self.move_to_the_heap();
-        self.insert(index, item)
-      } else {
-        a.insert(index, item);
-      },
+      TinyVec::Inline(a) if a.len() < A::CAPACITY => a.insert(index, item),
+      TinyVec::Inline(a) => {
+        assert!(
+          index <= a.len(),
+          "TinyVec::insert> index {} is out of bounds (len {})",
+          index,
+          a.len()
+        );
+        let mut v = Vec::with_capacity(A::CAPACITY * 2);
+        let mut moving = a.drain(..);
+        v.extend(moving.by_ref().take(index));
+        v.push(item);
+        v.extend(moving);
+        *self = TinyVec::Heap(v);
+      }
       TinyVec::Heap(v) => v.insert(index, item),
     }
   }
 
+  /// Clones the elements of the slice into the vec at `index`, shifting the
+  /// elements after it up.
+  ///
+  /// Room for the whole slice is made up front (moving to the heap if it
+  /// doesn't fit inline), and the tail is shifted only once.
+  ///
+  /// ## Panics
+  /// * If `index` > `len`
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([i32; 4], 1, 2);
+  /// tv.insert_from_slice(1, &[7, 8, 9]);
+  /// assert_eq!(tv.as_slice(), &[1, 7, 8, 9, 2][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn insert_from_slice(&mut self, index: usize, sli: &[A::Item])
+  where
+    A::Item: Clone,
+  {
+    assert!(
+      index <= self.len(),
+      "TinyVec::insert_from_slice> index {} is out of bounds (len {})",
+      index,
+      self.len()
+    );
+    self.reserve(sli.len());
+    self.extend_from_slice(sli);
+    self[index..].rotate_right(sli.len());
+  }
+
+  /// The number of elements that fit inline, before moving to the heap.
+  ///
+  /// This is the capacity of the backing array type.
+  #[inline(always)]
+  #[must_use]
+  pub fn inline_capacity() -> usize {
+    A::CAPACITY
+  }
+
+  /// Converts the vec into a boxed slice.
+  ///
+  /// This goes through [`into_vec`](TinyVec::<A>::into_vec), so a heap vec
+  /// only reallocates if it has spare capacity to shed.
+  #[inline]
+  #[must_use]
+  pub fn into_boxed_slice(self) -> Box<[A::Item]> {
+    self.into_vec().into_boxed_slice()
+  }
+
+  /// Converts the vec into a `Vec`.
+  ///
+  /// A heap vec gives back its `Vec` as is. An inline vec moves its elements
+  /// into a new `Vec` with exactly enough capacity for them.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let tv = tiny_vec!([i32; 4], 1, 2, 3);
+  /// let v = tv.into_vec();
+  /// assert_eq!(v, vec![1, 2, 3]);
+  /// assert_eq!(v.capacity(), 3);
+  /// ```
+  #[inline]
+  #[must_use]
+  pub fn into_vec(self) -> Vec<A::Item> {
+    match self {
+      TinyVec::Inline(mut a) => {
+        let mut v = Vec::with_capacity(a.len());
+        v.extend(a.drain(..));
+        v
+      }
+      TinyVec::Heap(v) => v,
+    }
+  }
+
   /// If the vec is empty.
   #[inline(always)]
   #[must_use]
@@ -338,6 +797,32 @@ impl<A: Array> TinyVec<A> {
     self.len() == 0
   }
 
+  /// If the vec has moved to the heap.
+  #[inline(always)]
+  #[must_use]
+  pub fn is_heap(&self) -> bool {
+    match self {
+      TinyVec::Inline(_) => false,
+      TinyVec::Heap(_) => true,
+    }
+  }
+
+  /// If the vec is still inline.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([i32; 2], 1, 2);
+  /// assert!(tv.is_inline());
+  /// tv.push(3);
+  /// assert!(tv.is_heap());
+  /// ```
+  #[inline(always)]
+  #[must_use]
+  pub fn is_inline(&self) -> bool {
+    !self.is_heap()
+  }
+
   /// The length of the vec (in elements).
   #[inline(always)]
   #[must_use]
@@ -351,10 +836,7 @@ impl<A: Array> TinyVec<A> {
   /// Makes a new, empty vec.
   #[inline(always)]
   #[must_use]
-  pub fn new() -> Self
-  where
-    A: Default,
-  {
+  pub fn new() -> Self {
     Self::default()
   }
 
@@ -403,6 +885,7 @@ impl<A: Array> TinyVec<A> {
   /// assert_eq!(tv.as_slice(), &[1, 3][..]);
   /// ```
   #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
   pub fn remove(&mut self, index: usize) -> A::Item {
     match self {
       TinyVec::Inline(a) => a.remove(index),
@@ -412,11 +895,37 @@ impl<A: Array> TinyVec<A> {
 
   // NIGHTLY: remove_item, https://github.com/rust-lang/rust/issues/40062
 
+  /// Makes sure there's room for at least `n` more elements.
+  ///
+  /// If they fit inline this does nothing, otherwise it's the same as
+  /// [`move_to_the_heap_and_reserve`](TinyVec::<A>::move_to_the_heap_and_reserve).
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([i32; 4], 1, 2);
+  /// tv.reserve(2);
+  /// assert!(tv.is_inline());
+  /// tv.reserve(3);
+  /// assert!(tv.is_heap());
+  /// assert!(tv.capacity() >= 5);
+  /// ```
+  #[inline]
+  pub fn reserve(&mut self, n: usize) {
+    match self {
+      TinyVec::Inline(a) if n <= A::CAPACITY - a.len() => (),
+      _ => self.move_to_the_heap_and_reserve(n),
+    }
+  }
+
   /// Resize the vec to the new length.
   ///
   /// If it needs to be longer, it's filled with clones of the provided value.
   /// If it needs to be shorter, it's truncated.
   ///
+  /// Growing an inline vec past its capacity moves it to the heap, with a
+  /// single allocation for the full new length.
+  ///
   /// ## Example
   ///
   /// ```rust
@@ -436,12 +945,12 @@ impl<A: Array> TinyVec<A> {
     A::Item: Clone,
   {
     match self {
-      TinyVec::Inline(a) => if new_len > A::CAPACITY {
-        self.move_to_the_heap();
+      TinyVec::Inline(a) if new_len > A::CAPACITY => {
+        let n = new_len - a.len();
+        self.move_to_the_heap_and_reserve(n);
         self.resize(new_len, new_val);
-      } else {
-        a.resize(new_len, new_val);
-      },
+      }
+      TinyVec::Inline(a) => a.resize(new_len, new_val),
       TinyVec::Heap(v) => v.resize(new_len, new_val),
     }
   }
@@ -451,6 +960,9 @@ impl<A: Array> TinyVec<A> {
   /// If it needs to be longer, it's filled with repeated calls to the provided
   /// function. If it needs to be shorter, it's truncated.
   ///
+  /// Growing past the inline capacity moves to the heap the same way as
+  /// [`resize`](TinyVec::<A>::resize) does.
+  ///
   /// ## Example
   ///
   /// ```rust
@@ -475,6 +987,11 @@ impl<A: Array> TinyVec<A> {
     f: F,
   ) {
     match self {
+      TinyVec::Inline(a) if new_len > A::CAPACITY => {
+        let n = new_len - a.len();
+        self.move_to_the_heap_and_reserve(n);
+        self.resize_with(new_len, f);
+      }
       TinyVec::Inline(a) => a.resize_with(new_len, f),
       TinyVec::Heap(v) => v.resize_with(new_len, f),
     }
@@ -491,6 +1008,9 @@ impl<A: Array> TinyVec<A> {
   /// tv.retain(|&x| x % 2 == 0);
   /// assert_eq!(tv.as_slice(), &[2, 4][..]);
   /// ```
+  ///
+  /// The vec keeps its variant: a heap vec stays on the heap even if the
+  /// remaining elements would fit inline.
   #[inline]
   pub fn retain<F: FnMut(&A::Item) -> bool>(&mut self, acceptable: F) {
     match self {
@@ -499,34 +1019,222 @@ impl<A: Array> TinyVec<A> {
     }
   }
 
-  // LATER(Vec): splice
-
-  /// Splits the collection at the point given.
+  /// Like [`retain`](TinyVec::<A>::retain), but the predicate gets mutable
+  /// access to each element.
+  ///
+  /// The vec keeps its variant, same as with `retain`.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use tinyvec::*;
+  ///
+  /// let mut tv = tiny_vec!([i32; 10], 1, 2, 3, 4);
+  /// tv.retain_mut(|x| {
+  ///   *x *= 10;
+  ///   *x > 20
+  /// });
+  /// assert_eq!(tv.as_slice(), &[30, 40][..]);
+  /// ```
+  #[inline]
+  pub fn retain_mut<F: FnMut(&mut A::Item) -> bool>(
+    &mut self,
+    mut acceptable: F,
+  ) {
+    match self {
+      TinyVec::Inline(a) => a.retain_mut(acceptable),
+      TinyVec::Heap(v) => {
+        // `Vec::retain_mut` is newer than our MSRV, so this does the same
+        // swapping walk as `ArrayVec`. The rejected elements collect in
+        // `[kept..processed]`, and the guard drains them out at the end (or
+        // during a panic), which leaves everything else in order.
+        let mut guard = HeapRetainGuard { v, kept: 0, processed: 0 };
+        while guard.processed < guard.v.len() {
+          let i = guard.processed;
+          if acceptable(&mut guard.v[i]) {
+            guard.v.swap(guard.kept, i);
+            guard.kept += 1;
+          }
+          guard.processed += 1;
+        }
+      }
+    }
+  }
+
+  /// Shrinks the capacity of the vec as much as possible.
+  ///
+  /// A heap vec whose elements fit inline moves back inline, freeing its
+  /// allocation. Otherwise this is `Vec::shrink_to_fit`. Inline vecs are left
+  /// alone.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv: TinyVec<[i32; 4]> = (1..=10).collect();
+  /// tv.truncate(3);
+  /// tv.shrink_to_fit();
+  /// assert_eq!(tv.as_slice(), &[1, 2, 3][..]);
+  /// assert!(tv.is_inline());
+  /// ```
+  #[inline]
+  pub fn shrink_to_fit(&mut self) {
+    if !self.try_move_to_inline() {
+      if let TinyVec::Heap(v) = self {
+        v.shrink_to_fit()
+      }
+    }
+  }
+
+  /// Creates a splicing iterator that removes the specified range in the
+  /// vector, yields the removed items, and replaces them with the items from
+  /// `replace_with`.
+  ///
+  /// Like with `Vec::splice`, the replacement happens when the iterator is
+  /// dropped, whether or not all the removed items were taken out of it.
+  /// This never fails for capacity: if the vec grows past the inline capacity
+  /// it moves to the heap, reserving room for the replacements by their size
+  /// hint.
+  ///
+  /// ## Panics
+  /// * If the start is greater than the end
+  /// * If the end is past the edge of the vec.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([i32; 4], 1, 2, 3);
+  /// let removed: Vec<i32> = tv.splice(1..2, vec![7, 8, 9]).collect();
+  /// assert_eq!(removed, vec![2]);
+  /// assert_eq!(tv.as_slice(), &[1, 7, 8, 9, 3][..]);
+  ///
+  /// tv.splice(..3, None);
+  /// assert_eq!(tv.as_slice(), &[9, 3][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn splice<R, I>(
+    &mut self,
+    range: R,
+    replace_with: I,
+  ) -> TinyVecSplice<'_, A, I::IntoIter>
+  where
+    R: RangeBounds<usize>,
+    I: IntoIterator<Item = A::Item>,
+  {
+    use core::ops::Bound;
+    let start = match range.start_bound() {
+      Bound::Included(x) => *x,
+      Bound::Excluded(x) => x + 1,
+      Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+      Bound::Included(x) => x + 1,
+      Bound::Excluded(x) => *x,
+      Bound::Unbounded => self.len(),
+    };
+    assert!(
+      start <= end,
+      "TinyVec::splice> illegal range {} to {} (len {})",
+      start,
+      end,
+      self.len()
+    );
+    assert!(
+      end <= self.len(),
+      "TinyVec::splice> range end {} is out of bounds (len {})",
+      end,
+      self.len()
+    );
+    TinyVecSplice {
+      parent: self,
+      removal_start: start,
+      removal_end: end,
+      target_index: start,
+      target_end: end,
+      replacement: replace_with.into_iter(),
+    }
+  }
+
+  /// Splits the collection at the point given.
+  ///
+  /// * `[0, at)` stays in this vec
+  /// * `[at, len)` ends up in the new vec.
+  ///
+  /// The new vec is inline whenever the tail fits in the inline capacity, even
+  /// when this vec is on the heap. This vec keeps its variant.
+  ///
+  /// ## Panics
+  /// * if at > len
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([i32; 4], 1, 2, 3);
+  /// let tv2 = tv.split_off(1);
+  /// assert_eq!(tv.as_slice(), &[1][..]);
+  /// assert_eq!(tv2.as_slice(), &[2, 3][..]);
+  /// ```
+  #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn split_off(&mut self, at: usize) -> Self {
+    match self {
+      TinyVec::Inline(a) => TinyVec::Inline(a.split_off(at)),
+      TinyVec::Heap(v) => {
+        assert!(
+          at <= v.len(),
+          "TinyVec::split_off> index {} is out of bounds (len {})",
+          at,
+          v.len()
+        );
+        if v.len() - at <= A::CAPACITY {
+          TinyVec::Inline(v.drain(at..).collect())
+        } else {
+          TinyVec::Heap(v.split_off(at))
+        }
+      }
+    }
+  }
+
+  /// Splits off `[at, len)` into an `ArrayVec`, if it fits in the inline
+  /// capacity.
   ///
-  /// * `[0, at)` stays in this vec
-  /// * `[at, len)` ends up in the new vec.
+  /// This vec keeps `[0, at)` and its variant.
+  ///
+  /// ## Failure
+  /// * If the tail is longer than `A::CAPACITY` you get its length in the
+  ///   `Err`, and this vec is left unchanged.
   ///
   /// ## Panics
   /// * if at > len
   ///
   /// ## Example
-  ///
   /// ```rust
   /// use tinyvec::*;
-  /// let mut tv = tiny_vec!([i32; 4], 1, 2, 3);
-  /// let tv2 = tv.split_off(1);
-  /// assert_eq!(tv.as_slice(), &[1][..]);
-  /// assert_eq!(tv2.as_slice(), &[2, 3][..]);
+  /// let mut tv: TinyVec<[i32; 2]> = (1..=5).collect();
+  /// assert_eq!(tv.split_off_inline(1), Err(4));
+  /// let tail = tv.split_off_inline(3).unwrap();
+  /// assert_eq!(tail.as_slice(), &[4, 5][..]);
+  /// assert_eq!(tv.as_slice(), &[1, 2, 3][..]);
+  /// assert!(tv.is_heap());
   /// ```
   #[inline]
-  pub fn split_off(&mut self, at: usize) -> Self
-  where
-    A: Default,
-  {
-    match self {
-      TinyVec::Inline(a) => TinyVec::Inline(a.split_off(at)),
-      TinyVec::Heap(v) => TinyVec::Heap(v.split_off(at)),
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  pub fn split_off_inline(&mut self, at: usize) -> Result<ArrayVec<A>, usize> {
+    let len = self.len();
+    assert!(
+      at <= len,
+      "TinyVec::split_off_inline> index {} is out of bounds (len {})",
+      at,
+      len
+    );
+    if len - at > A::CAPACITY {
+      return Err(len - at);
     }
+    Ok(match self {
+      TinyVec::Inline(a) => a.split_off(at),
+      TinyVec::Heap(v) => v.drain(at..).collect(),
+    })
   }
 
   /// Remove an element, swapping the end of the vec into its place.
@@ -546,6 +1254,7 @@ impl<A: Array> TinyVec<A> {
   /// assert_eq!(tv.as_slice(), &["quack", "zap"][..]);
   /// ```
   #[inline]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
   pub fn swap_remove(&mut self, index: usize) -> A::Item {
     match self {
       TinyVec::Inline(a) => a.swap_remove(index),
@@ -554,8 +1263,14 @@ impl<A: Array> TinyVec<A> {
   }
 
   /// Reduces the vec's length to the given value.
-  /// 
+  ///
   /// If the vec is already shorter than the input, nothing happens.
+  ///
+  /// This never changes the variant and never releases capacity: a heap vec
+  /// stays on the heap with the same allocation, even when the remaining
+  /// elements would fit inline. Use
+  /// [`shrink_to_fit`](TinyVec::<A>::shrink_to_fit) or
+  /// [`try_move_to_inline`](TinyVec::<A>::try_move_to_inline) for that.
   #[inline]
   pub fn truncate(&mut self, new_len: usize) {
     match self {
@@ -564,6 +1279,65 @@ impl<A: Array> TinyVec<A> {
     }
   }
 
+  /// Tries to reserve room for at least `additional` more elements.
+  ///
+  /// If they fit inline this does nothing. Otherwise the vec moves to the heap
+  /// (or grows its heap allocation), like `Vec::try_reserve` does.
+  ///
+  /// ## Failure
+  /// * If the allocation fails, or the capacity would overflow, you get the
+  ///   error from the allocator and the vec is left unchanged.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv = tiny_vec!([i32; 4], 1, 2);
+  /// assert!(tv.try_reserve(2).is_ok());
+  /// assert_eq!(tv.as_slice(), &[1, 2][..]);
+  /// assert!(tv.try_reserve(usize::MAX).is_err());
+  /// ```
+  #[cfg(feature = "rustc_1_57")]
+  #[inline]
+  pub fn try_reserve(
+    &mut self,
+    additional: usize,
+  ) -> Result<(), TryReserveError> {
+    match self {
+      TinyVec::Inline(a) if additional <= A::CAPACITY - a.len() => Ok(()),
+      TinyVec::Inline(a) => {
+        let mut v = Vec::new();
+        v.try_reserve(a.len().saturating_add(additional))?;
+        v.extend(a.drain(..));
+        *self = TinyVec::Heap(v);
+        Ok(())
+      }
+      TinyVec::Heap(v) => v.try_reserve(additional),
+    }
+  }
+
+  /// Tries to reserve room for exactly `additional` more elements.
+  ///
+  /// Same as [`try_reserve`](TinyVec::<A>::try_reserve), but the heap
+  /// allocation isn't rounded up, like `Vec::try_reserve_exact`.
+  #[cfg(feature = "rustc_1_57")]
+  #[inline]
+  pub fn try_reserve_exact(
+    &mut self,
+    additional: usize,
+  ) -> Result<(), TryReserveError> {
+    match self {
+      TinyVec::Inline(a) if additional <= A::CAPACITY - a.len() => Ok(()),
+      TinyVec::Inline(a) => {
+        let mut v = Vec::new();
+        v.try_reserve_exact(a.len().saturating_add(additional))?;
+        v.extend(a.drain(..));
+        *self = TinyVec::Heap(v);
+        Ok(())
+      }
+      TinyVec::Heap(v) => v.try_reserve_exact(additional),
+    }
+  }
+
   /// Wraps an array, using the given length as the starting length.
   ///
   /// If you want to use the whole length of the array, you can just use the
@@ -578,35 +1352,212 @@ impl<A: Array> TinyVec<A> {
     let arr = ArrayVec::try_from_array_len(data, len)?;
     Ok(TinyVec::Inline(arr))
   }
+
+  /// Moves the content back inline, if it's on the heap and it fits.
+  ///
+  /// Returns if the vec is inline afterwards: `true` if it already was or if
+  /// the elements were moved (and the heap allocation freed), `false` if there
+  /// are too many elements. Unlike
+  /// [`shrink_to_fit`](TinyVec::<A>::shrink_to_fit) this leaves the heap
+  /// allocation alone when the elements don't fit.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let mut tv: TinyVec<[i32; 4]> = (1..=6).collect();
+  /// assert!(!tv.try_move_to_inline());
+  /// tv.truncate(4);
+  /// assert!(tv.try_move_to_inline());
+  /// assert!(tv.is_inline());
+  /// assert_eq!(tv.as_slice(), &[1, 2, 3, 4][..]);
+  /// ```
+  #[inline]
+  pub fn try_move_to_inline(&mut self) -> bool {
+    match self {
+      TinyVec::Inline(_) => true,
+      TinyVec::Heap(v) if v.len() <= A::CAPACITY => {
+        let arr: ArrayVec<A> = v.drain(..).collect();
+        *self = TinyVec::Inline(arr);
+        true
+      }
+      TinyVec::Heap(_) => false,
+    }
+  }
+
+  /// Makes a new, empty vec with room for at least `n` elements.
+  ///
+  /// If `n` fits in the inline capacity the vec is inline, otherwise it starts
+  /// out on the heap.
+  ///
+  /// ## Example
+  /// ```rust
+  /// use tinyvec::*;
+  /// let tv: TinyVec<[i32; 4]> = TinyVec::with_capacity(3);
+  /// assert!(tv.is_inline());
+  /// let tv: TinyVec<[i32; 4]> = TinyVec::with_capacity(5);
+  /// assert!(tv.is_heap());
+  /// assert!(tv.capacity() >= 5);
+  /// ```
+  #[inline]
+  #[must_use]
+  pub fn with_capacity(n: usize) -> Self {
+    if n <= A::CAPACITY {
+      TinyVec::Inline(ArrayVec::default())
+    } else {
+      TinyVec::Heap(Vec::with_capacity(n))
+    }
+  }
 }
 
-/// Draining iterator for `TinyVecDrain`
-/// 
-/// See [`TinyVecDrain::drain`](TinyVecDrain::<A>::drain)
-pub struct TinyVecDrain<'p, A: Array> {
-  parent: &'p mut TinyVec<A>,
-  target_index: usize,
-  target_count: usize,
+/// Finishes a heap `retain_mut` pass when dropped, even if the predicate
+/// panicked.
+///
+/// `[0, kept)` holds the kept elements, `[kept, processed)` holds the rejected
+/// ones, and `[processed, len)` hasn't been looked at yet.
+struct HeapRetainGuard<'v, T> {
+  v: &'v mut Vec<T>,
+  kept: usize,
+  processed: usize,
+}
+impl<'v, T> Drop for HeapRetainGuard<'v, T> {
+  #[inline]
+  fn drop(&mut self) {
+    self.v.drain(self.kept..self.processed);
+  }
+}
+
+/// Draining iterator for `TinyVec`
+///
+/// See [`TinyVec::drain`](TinyVec::<A>::drain)
+pub enum TinyVecDrain<'p, A: Array> {
+  #[allow(missing_docs)]
+  Inline(ArrayVecDrain<'p, A>),
+  #[allow(missing_docs)]
+  Heap(alloc::vec::Drain<'p, A::Item>),
 }
-// GoodFirstIssue: this entire type is correct but slow.
 // NIGHTLY: vec_drain_as_slice, https://github.com/rust-lang/rust/issues/58957
 impl<'p, A: Array> Iterator for TinyVecDrain<'p, A> {
   type Item = A::Item;
   #[inline]
   fn next(&mut self) -> Option<Self::Item> {
-    if self.target_count > 0 {
-      let out = self.parent.remove(self.target_index);
-      self.target_count -= 1;
-      Some(out)
+    match self {
+      TinyVecDrain::Inline(a) => a.next(),
+      TinyVecDrain::Heap(v) => v.next(),
+    }
+  }
+  #[inline(always)]
+  #[must_use]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    match self {
+      TinyVecDrain::Inline(a) => a.size_hint(),
+      TinyVecDrain::Heap(v) => v.size_hint(),
+    }
+  }
+}
+impl<'p, A: Array> DoubleEndedIterator for TinyVecDrain<'p, A> {
+  #[inline]
+  fn next_back(&mut self) -> Option<Self::Item> {
+    match self {
+      TinyVecDrain::Inline(a) => a.next_back(),
+      TinyVecDrain::Heap(v) => v.next_back(),
+    }
+  }
+}
+impl<'p, A: Array> ExactSizeIterator for TinyVecDrain<'p, A> {}
+
+/// Splicing iterator for `TinyVec`
+///
+/// See [`TinyVec::splice`](TinyVec::<A>::splice)
+///
+/// While the splice is alive the removed items are taken out of the range in
+/// place (leaving defaults behind). When it's dropped the range is refilled
+/// from the replacements, and the rest of the range removed or the remaining
+/// replacements inserted.
+pub struct TinyVecSplice<'p, A: Array, I: Iterator<Item = A::Item>> {
+  parent: &'p mut TinyVec<A>,
+  removal_start: usize,
+  removal_end: usize,
+  /// The next item to yield from the front.
+  target_index: usize,
+  /// One past the next item to yield from the back.
+  target_end: usize,
+  replacement: I,
+}
+impl<'p, A, I> Iterator for TinyVecSplice<'p, A, I>
+where
+  A: Array,
+  I: Iterator<Item = A::Item>,
+{
+  type Item = A::Item;
+  #[inline]
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.target_index < self.target_end {
+      let slot = &mut self.parent[self.target_index];
+      self.target_index += 1;
+      Some(replace(slot, A::Item::default()))
+    } else {
+      None
+    }
+  }
+  #[inline(always)]
+  #[must_use]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let s = self.target_end - self.target_index;
+    (s, Some(s))
+  }
+}
+impl<'p, A, I> DoubleEndedIterator for TinyVecSplice<'p, A, I>
+where
+  A: Array,
+  I: Iterator<Item = A::Item>,
+{
+  #[inline]
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.target_index < self.target_end {
+      self.target_end -= 1;
+      let slot = &mut self.parent[self.target_end];
+      Some(replace(slot, A::Item::default()))
     } else {
       None
     }
   }
 }
-impl<'p, A: Array> Drop for TinyVecDrain<'p, A> {
+impl<'p, A, I> ExactSizeIterator for TinyVecSplice<'p, A, I>
+where
+  A: Array,
+  I: Iterator<Item = A::Item>,
+{
+}
+impl<'p, A, I> Drop for TinyVecSplice<'p, A, I>
+where
+  A: Array,
+  I: Iterator<Item = A::Item>,
+{
   #[inline]
   fn drop(&mut self) {
-    for _ in self {}
+    // Overwrite the removed range first (which drops anything not yielded).
+    let mut filled = self.removal_start;
+    while filled < self.removal_end {
+      match self.replacement.next() {
+        Some(item) => self.parent[filled] = item,
+        None => break,
+      }
+      filled += 1;
+    }
+    if filled < self.removal_end {
+      // Ran out of replacements, so close the gap.
+      self.parent.drain(filled..self.removal_end);
+    } else {
+      // Anything left over goes on the end, and then gets rotated into place
+      // in front of the tail.
+      let old_len = self.parent.len();
+      self.parent.reserve(self.replacement.size_hint().0);
+      for item in &mut self.replacement {
+        self.parent.push(item);
+      }
+      let added = self.parent.len() - old_len;
+      self.parent[self.removal_end..].rotate_right(added);
+    }
   }
 }
 
@@ -645,6 +1596,10 @@ impl<A: Array> BorrowMut<[A::Item]> for TinyVec<A> {
 impl<A: Array> Extend<A::Item> for TinyVec<A> {
   #[inline]
   fn extend<T: IntoIterator<Item = A::Item>>(&mut self, iter: T) {
+    // Only the lower bound of the hint is trusted for the reservation. If the
+    // iterator yields more than that, `push` keeps things working anyway.
+    let iter = iter.into_iter();
+    self.reserve(iter.size_hint().0);
     for t in iter {
       self.push(t)
     }
@@ -659,15 +1614,86 @@ impl<A: Array> From<ArrayVec<A>> for TinyVec<A> {
   }
 }
 
-impl<A: Array + Default> FromIterator<A::Item> for TinyVec<A> {
+impl<A: Array> From<Vec<A::Item>> for TinyVec<A> {
+  /// Same as [`TinyVec::from_vec`], the `Vec` is kept as is.
+  #[inline(always)]
+  #[must_use]
+  fn from(v: Vec<A::Item>) -> Self {
+    TinyVec::from_vec(v)
+  }
+}
+
+impl<'s, A: Array> From<&'s [A::Item]> for TinyVec<A>
+where
+  A::Item: Clone,
+{
+  /// Inline if the slice fits, otherwise a heap vec with room for exactly the
+  /// slice's elements.
   #[inline]
   #[must_use]
-  fn from_iter<T: IntoIterator<Item = A::Item>>(iter: T) -> Self {
-    let mut av = Self::default();
-    for i in iter {
-      av.push(i)
+  fn from(sli: &'s [A::Item]) -> Self {
+    if sli.len() <= A::CAPACITY {
+      let mut arr = ArrayVec::new();
+      arr.extend_from_slice(sli);
+      TinyVec::Inline(arr)
+    } else {
+      TinyVec::Heap(sli.to_vec())
     }
-    av
+  }
+}
+
+impl<'s, A: Array> From<&'s mut [A::Item]> for TinyVec<A>
+where
+  A::Item: Clone,
+{
+  /// Same as the `From<&[A::Item]>` impl.
+  #[inline(always)]
+  #[must_use]
+  fn from(sli: &'s mut [A::Item]) -> Self {
+    Self::from(&*sli)
+  }
+}
+
+impl<A: Array> FromIterator<A::Item> for TinyVec<A> {
+  #[inline]
+  #[must_use]
+  fn from_iter<T: IntoIterator<Item = A::Item>>(iter: T) -> Self {
+    let iter = iter.into_iter();
+    let mut tv = Self::with_capacity(iter.size_hint().0);
+    tv.extend(iter);
+    tv
+  }
+}
+
+/// Appends the written bytes to the vec, moving to the heap as needed.
+///
+/// Since a `TinyVec` can always grow, `write` takes the whole buffer every time
+/// and never does a short write (a full fixed-capacity buffer would have to).
+/// `flush` does nothing.
+///
+/// ## Example
+/// ```rust
+/// use std::io::Write;
+/// use tinyvec::*;
+/// let mut tv: TinyVec<[u8; 8]> = TinyVec::new();
+/// write!(tv, "{}-{}", "hello", "world").unwrap();
+/// assert_eq!(tv.as_slice(), b"hello-world");
+/// ```
+#[cfg(feature = "std")]
+impl<A: Array<Item = u8>> std::io::Write for TinyVec<A> {
+  #[inline]
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    self.extend_from_slice(buf);
+    Ok(buf.len())
+  }
+  #[inline]
+  fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+    self.extend_from_slice(buf);
+    Ok(())
+  }
+  #[inline(always)]
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
   }
 }
 
@@ -676,7 +1702,7 @@ pub enum TinyVecIterator<A: Array> {
   #[allow(missing_docs)]
   Inline(ArrayVecIterator<A>),
   #[allow(missing_docs)]
-  Heap(alloc::vec::IntoIter<A::Item>)
+  Heap(alloc::vec::IntoIter<A::Item>),
 }
 impl<A: Array> Iterator for TinyVecIterator<A> {
   type Item = A::Item;
@@ -717,6 +1743,17 @@ impl<A: Array> Iterator for TinyVecIterator<A> {
     }
   }
 }
+impl<A: Array> DoubleEndedIterator for TinyVecIterator<A> {
+  #[inline]
+  fn next_back(&mut self) -> Option<Self::Item> {
+    match self {
+      TinyVecIterator::Inline(a) => a.next_back(),
+      TinyVecIterator::Heap(v) => v.next_back(),
+    }
+  }
+}
+impl<A: Array> ExactSizeIterator for TinyVecIterator<A> {}
+impl<A: Array> FusedIterator for TinyVecIterator<A> {}
 
 impl<A: Array> IntoIterator for TinyVec<A> {
   type Item = A::Item;
@@ -764,6 +1801,19 @@ where
   }
 }
 
+/// Hashes the same way as the slice of active elements, whichever variant the
+/// vec is, so a `TinyVec` key can be looked up by `&[T]` (through the `Borrow`
+/// impl) or by a `TinyVec` of the other variant.
+impl<A: Array> Hash for TinyVec<A>
+where
+  A::Item: Hash,
+{
+  #[inline]
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.as_slice().hash(state)
+  }
+}
+
 impl<A: Array> PartialEq<&A> for TinyVec<A>
 where
   A::Item: PartialEq,
@@ -786,6 +1836,43 @@ where
   }
 }
 
+/// Compares against an array of any length by value. (Comparing against a
+/// reference to an array only works for the backing array type, through the
+/// impl for `&A`, since the two impls would overlap otherwise.)
+#[cfg(feature = "rustc_1_55")]
+impl<A: Array, const N: usize> PartialEq<[A::Item; N]> for TinyVec<A>
+where
+  A::Item: PartialEq,
+{
+  #[inline]
+  #[must_use]
+  fn eq(&self, other: &[A::Item; N]) -> bool {
+    self.as_slice() == &other[..]
+  }
+}
+
+impl<A: Array> PartialEq<Vec<A::Item>> for TinyVec<A>
+where
+  A::Item: PartialEq,
+{
+  #[inline]
+  #[must_use]
+  fn eq(&self, other: &Vec<A::Item>) -> bool {
+    self.as_slice() == other.as_slice()
+  }
+}
+
+impl<A: Array, B: Array<Item = A::Item>> PartialEq<ArrayVec<B>> for TinyVec<A>
+where
+  A::Item: PartialEq,
+{
+  #[inline]
+  #[must_use]
+  fn eq(&self, other: &ArrayVec<B>) -> bool {
+    self.as_slice() == other.as_slice()
+  }
+}
+
 /*
 
 I think, in retrospect, this is useless?
@@ -806,158 +1893,159 @@ where
 */
 
 // //
-// Formatting impls
+// Test case generation impls
 // //
 
-impl<A: Array> Binary for TinyVec<A>
+/// Generates lengths up to twice the inline capacity, so that both variants
+/// come up about as often.
+#[cfg(feature = "arbitrary")]
+impl<'a, A: Array> arbitrary::Arbitrary<'a> for TinyVec<A>
 where
-  A::Item: Binary,
+  A::Item: arbitrary::Arbitrary<'a>,
 {
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      Binary::fmt(elem, f)?;
+  #[inline]
+  fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+    let len = u.int_in_range(0..=A::CAPACITY.saturating_mul(2))?;
+    let mut tv = TinyVec::with_capacity(len);
+    for _ in 0..len {
+      tv.push(arbitrary::Arbitrary::arbitrary(u)?);
     }
-    write!(f, "]")
+    Ok(tv)
   }
 }
 
-impl<A: Array> Debug for TinyVec<A>
+/// Generates lengths up to twice the inline capacity, so that both variants
+/// come up about as often. Shrinking removes elements, and a vec that shrinks
+/// to fit inline is generated inline.
+#[cfg(feature = "proptest")]
+impl<A: Array> proptest::arbitrary::Arbitrary for TinyVec<A>
 where
-  A::Item: Debug,
+  A::Item: proptest::arbitrary::Arbitrary,
 {
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      Debug::fmt(elem, f)?;
-    }
-    write!(f, "]")
+  type Parameters = <A::Item as proptest::arbitrary::Arbitrary>::Parameters;
+  type Strategy = proptest::strategy::Map<
+    proptest::collection::VecStrategy<
+      <A::Item as proptest::arbitrary::Arbitrary>::Strategy,
+    >,
+    fn(Vec<A::Item>) -> Self,
+  >;
+  #[inline]
+  fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+    use proptest::strategy::Strategy;
+    let element = proptest::arbitrary::any_with::<A::Item>(args);
+    let lengths = 0..=A::CAPACITY.saturating_mul(2);
+    // `collect` picks the variant from the exact length.
+    let to_tiny_vec: fn(Vec<A::Item>) -> Self = |v| v.into_iter().collect();
+    proptest::collection::vec(element, lengths).prop_map(to_tiny_vec)
   }
 }
 
-impl<A: Array> Display for TinyVec<A>
-where
-  A::Item: Display,
-{
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      Display::fmt(elem, f)?;
-    }
-    write!(f, "]")
-  }
-}
+// //
+// Rayon impls
+// //
 
-impl<A: Array> LowerExp for TinyVec<A>
+/// An inline vec is moved into a `Vec` first, so both variants share rayon's
+/// `Vec` producer.
+#[cfg(feature = "rayon")]
+impl<A: Array> rayon::iter::IntoParallelIterator for TinyVec<A>
 where
-  A::Item: LowerExp,
+  A::Item: Send,
 {
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      LowerExp::fmt(elem, f)?;
-    }
-    write!(f, "]")
+  type Item = A::Item;
+  type Iter = rayon::vec::IntoIter<A::Item>;
+  #[inline]
+  fn into_par_iter(self) -> Self::Iter {
+    self.into_vec().into_par_iter()
   }
 }
 
-impl<A: Array> LowerHex for TinyVec<A>
+#[cfg(feature = "rayon")]
+impl<'a, A: Array> rayon::iter::IntoParallelIterator for &'a TinyVec<A>
 where
-  A::Item: LowerHex,
+  A::Item: Sync + 'a,
 {
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      LowerHex::fmt(elem, f)?;
-    }
-    write!(f, "]")
+  type Item = &'a A::Item;
+  type Iter = rayon::slice::Iter<'a, A::Item>;
+  #[inline]
+  fn into_par_iter(self) -> Self::Iter {
+    self.as_slice().into_par_iter()
   }
 }
 
-impl<A: Array> Octal for TinyVec<A>
+#[cfg(feature = "rayon")]
+impl<'a, A: Array> rayon::iter::IntoParallelIterator for &'a mut TinyVec<A>
 where
-  A::Item: Octal,
+  A::Item: Send + 'a,
 {
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      Octal::fmt(elem, f)?;
-    }
-    write!(f, "]")
+  type Item = &'a mut A::Item;
+  type Iter = rayon::slice::IterMut<'a, A::Item>;
+  #[inline]
+  fn into_par_iter(self) -> Self::Iter {
+    self.as_mut_slice().into_par_iter()
   }
 }
 
-impl<A: Array> Pointer for TinyVec<A>
+/// The items are collected on the heap, then moved inline if they fit.
+#[cfg(feature = "rayon")]
+impl<A: Array> rayon::iter::FromParallelIterator<A::Item> for TinyVec<A>
 where
-  A::Item: Pointer,
+  A::Item: Send,
 {
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      Pointer::fmt(elem, f)?;
-    }
-    write!(f, "]")
+  #[inline]
+  fn from_par_iter<I>(par_iter: I) -> Self
+  where
+    I: rayon::iter::IntoParallelIterator<Item = A::Item>,
+  {
+    let v: Vec<A::Item> =
+      rayon::iter::ParallelIterator::collect(par_iter.into_par_iter());
+    let mut tv = TinyVec::from_vec(v);
+    tv.try_move_to_inline();
+    tv
   }
 }
 
-impl<A: Array> UpperExp for TinyVec<A>
+#[cfg(feature = "rayon")]
+impl<A: Array> rayon::iter::ParallelExtend<A::Item> for TinyVec<A>
 where
-  A::Item: UpperExp,
+  A::Item: Send,
 {
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      UpperExp::fmt(elem, f)?;
-    }
-    write!(f, "]")
+  #[inline]
+  fn par_extend<I>(&mut self, par_iter: I)
+  where
+    I: rayon::iter::IntoParallelIterator<Item = A::Item>,
+  {
+    let v: Vec<A::Item> =
+      rayon::iter::ParallelIterator::collect(par_iter.into_par_iter());
+    self.extend(v);
   }
 }
 
-impl<A: Array> UpperHex for TinyVec<A>
+// //
+// Zeroize impls
+// //
+
+/// An inline vec zeroizes its whole array. A heap vec zeroizes its elements
+/// and the entire allocation (full capacity, not just `len`), and keeps the
+/// allocation. Either way the length becomes 0 and the variant is unchanged.
+#[cfg(feature = "zeroize")]
+impl<A: Array> zeroize::Zeroize for TinyVec<A>
 where
-  A::Item: UpperHex,
+  A::Item: zeroize::Zeroize,
 {
-  #[allow(clippy::missing_inline_in_public_items)]
-  fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-    write!(f, "[")?;
-    for (i, elem) in self.iter().enumerate() {
-      if i > 0 {
-        write!(f, ", ")?;
-      }
-      UpperHex::fmt(elem, f)?;
+  #[inline]
+  fn zeroize(&mut self) {
+    match self {
+      TinyVec::Inline(a) => a.zeroize(),
+      TinyVec::Heap(v) => v.zeroize(),
     }
-    write!(f, "]")
   }
 }
+
+// //
+// Formatting impls
+// //
+
+impl_list_formatting!(
+  TinyVec;
+  Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex
+);
@@ -0,0 +1,279 @@
+use super::*;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// The core operations shared by the vec-like types, so that generic code can
+/// accept any of them.
+///
+/// This is implemented for [`ArrayVec`], [`SmallArrayVec`], and (with the
+/// `alloc` feature) [`TinyVec`] and `Vec`.
+///
+/// Only a few methods are required, the rest have default impls in terms of
+/// those (the impls in this crate override them with the inherent methods).
+///
+/// ## Example
+/// ```rust
+/// use tinyvec::*;
+///
+/// fn collect_words<V: VecLike<Item = u32>>(out: &mut V, bytes: &[u8]) {
+///   for chunk in bytes.chunks(4) {
+///     let mut word = [0; 4];
+///     word[..chunk.len()].copy_from_slice(chunk);
+///     if out.try_push(u32::from_le_bytes(word)).is_err() {
+///       break;
+///     }
+///   }
+/// }
+///
+/// let bytes = [1, 0, 0, 0, 2, 0, 0, 0, 3];
+///
+/// let mut av: ArrayVec<[u32; 2]> = ArrayVec::new();
+/// collect_words(&mut av, &bytes);
+/// assert_eq!(av.as_slice(), &[1, 2][..]);
+///
+/// let mut sav: SmallArrayVec<[u32; 8], u8> = SmallArrayVec::new();
+/// collect_words(&mut sav, &bytes);
+/// assert_eq!(sav.as_slice(), &[1, 2, 3][..]);
+/// ```
+pub trait VecLike {
+  /// The type of the elements.
+  type Item;
+
+  /// The active elements as a shared slice.
+  fn as_slice(&self) -> &[Self::Item];
+
+  /// The active elements as a mutable slice.
+  fn as_mut_slice(&mut self) -> &mut [Self::Item];
+
+  /// Remove and return the last element, if there is one.
+  fn pop(&mut self) -> Option<Self::Item>;
+
+  /// Reduces the length to the given value, if it's currently longer.
+  fn truncate(&mut self, new_len: usize);
+
+  /// Place an element onto the end.
+  ///
+  /// ## Failure
+  /// * If there's no room for the element you get it back in the `Err`.
+  fn try_push(&mut self, item: Self::Item) -> Result<(), Self::Item>;
+
+  /// Place an element onto the end.
+  ///
+  /// ## Panics
+  /// * If there's no room for the element.
+  #[inline]
+  fn push(&mut self, item: Self::Item) {
+    if self.try_push(item).is_err() {
+      panic!("VecLike::push> capacity overflow (len {})", self.len())
+    }
+  }
+
+  /// The number of active elements.
+  #[inline]
+  fn len(&self) -> usize {
+    self.as_slice().len()
+  }
+
+  /// If there are no active elements.
+  #[inline]
+  fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Removes all elements.
+  #[inline]
+  fn clear(&mut self) {
+    self.truncate(0)
+  }
+
+  /// Clone each element of the slice onto the end.
+  ///
+  /// ## Panics
+  /// * If the elements don't all fit.
+  #[inline]
+  fn extend_from_slice(&mut self, sli: &[Self::Item])
+  where
+    Self::Item: Clone,
+  {
+    for item in sli {
+      self.push(item.clone())
+    }
+  }
+}
+
+impl<A: Array> VecLike for ArrayVec<A> {
+  type Item = A::Item;
+  #[inline(always)]
+  fn as_slice(&self) -> &[A::Item] {
+    ArrayVec::as_slice(self)
+  }
+  #[inline(always)]
+  fn as_mut_slice(&mut self) -> &mut [A::Item] {
+    ArrayVec::as_mut_slice(self)
+  }
+  #[inline(always)]
+  fn pop(&mut self) -> Option<A::Item> {
+    ArrayVec::pop(self)
+  }
+  #[inline(always)]
+  fn truncate(&mut self, new_len: usize) {
+    ArrayVec::truncate(self, new_len)
+  }
+  #[inline]
+  fn try_push(&mut self, item: A::Item) -> Result<(), A::Item> {
+    if ArrayVec::len(self) < A::CAPACITY {
+      ArrayVec::push(self, item);
+      Ok(())
+    } else {
+      Err(item)
+    }
+  }
+  #[inline(always)]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  fn push(&mut self, item: A::Item) {
+    ArrayVec::push(self, item)
+  }
+  #[inline(always)]
+  fn len(&self) -> usize {
+    ArrayVec::len(self)
+  }
+  #[inline(always)]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  fn extend_from_slice(&mut self, sli: &[A::Item])
+  where
+    A::Item: Clone,
+  {
+    ArrayVec::extend_from_slice(self, sli)
+  }
+}
+
+impl<A: Array, L: LenType> VecLike for SmallArrayVec<A, L> {
+  type Item = A::Item;
+  #[inline(always)]
+  fn as_slice(&self) -> &[A::Item] {
+    SmallArrayVec::as_slice(self)
+  }
+  #[inline(always)]
+  fn as_mut_slice(&mut self) -> &mut [A::Item] {
+    SmallArrayVec::as_mut_slice(self)
+  }
+  #[inline(always)]
+  fn pop(&mut self) -> Option<A::Item> {
+    SmallArrayVec::pop(self)
+  }
+  #[inline(always)]
+  fn truncate(&mut self, new_len: usize) {
+    SmallArrayVec::truncate(self, new_len)
+  }
+  #[inline]
+  fn try_push(&mut self, item: A::Item) -> Result<(), A::Item> {
+    if SmallArrayVec::len(self) < self.capacity() {
+      SmallArrayVec::push(self, item);
+      Ok(())
+    } else {
+      Err(item)
+    }
+  }
+  #[inline(always)]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  fn push(&mut self, item: A::Item) {
+    SmallArrayVec::push(self, item)
+  }
+  #[inline(always)]
+  fn len(&self) -> usize {
+    SmallArrayVec::len(self)
+  }
+  #[inline(always)]
+  #[cfg_attr(feature = "rustc_1_46", track_caller)]
+  fn extend_from_slice(&mut self, sli: &[A::Item])
+  where
+    A::Item: Clone,
+  {
+    SmallArrayVec::extend_from_slice(self, sli)
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<A: Array> VecLike for TinyVec<A> {
+  type Item = A::Item;
+  #[inline(always)]
+  fn as_slice(&self) -> &[A::Item] {
+    TinyVec::as_slice(self)
+  }
+  #[inline(always)]
+  fn as_mut_slice(&mut self) -> &mut [A::Item] {
+    TinyVec::as_mut_slice(self)
+  }
+  #[inline(always)]
+  fn pop(&mut self) -> Option<A::Item> {
+    TinyVec::pop(self)
+  }
+  #[inline(always)]
+  fn truncate(&mut self, new_len: usize) {
+    TinyVec::truncate(self, new_len)
+  }
+  /// A `TinyVec` always has room, so this never fails.
+  #[inline(always)]
+  fn try_push(&mut self, item: A::Item) -> Result<(), A::Item> {
+    TinyVec::push(self, item);
+    Ok(())
+  }
+  #[inline(always)]
+  fn push(&mut self, item: A::Item) {
+    TinyVec::push(self, item)
+  }
+  #[inline(always)]
+  fn len(&self) -> usize {
+    TinyVec::len(self)
+  }
+  #[inline(always)]
+  fn extend_from_slice(&mut self, sli: &[A::Item])
+  where
+    A::Item: Clone,
+  {
+    TinyVec::extend_from_slice(self, sli)
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> VecLike for Vec<T> {
+  type Item = T;
+  #[inline(always)]
+  fn as_slice(&self) -> &[T] {
+    Vec::as_slice(self)
+  }
+  #[inline(always)]
+  fn as_mut_slice(&mut self) -> &mut [T] {
+    Vec::as_mut_slice(self)
+  }
+  #[inline(always)]
+  fn pop(&mut self) -> Option<T> {
+    Vec::pop(self)
+  }
+  #[inline(always)]
+  fn truncate(&mut self, new_len: usize) {
+    Vec::truncate(self, new_len)
+  }
+  /// A `Vec` always has room, so this never fails.
+  #[inline(always)]
+  fn try_push(&mut self, item: T) -> Result<(), T> {
+    Vec::push(self, item);
+    Ok(())
+  }
+  #[inline(always)]
+  fn push(&mut self, item: T) {
+    Vec::push(self, item)
+  }
+  #[inline(always)]
+  fn len(&self) -> usize {
+    Vec::len(self)
+  }
+  #[inline(always)]
+  fn extend_from_slice(&mut self, sli: &[T])
+  where
+    T: Clone,
+  {
+    Vec::extend_from_slice(self, sli)
+  }
+}
@@ -1,7 +1,50 @@
 #![allow(bad_style)]
+#![allow(clippy::missing_const_for_thread_local)]
 
 use tinyvec::*;
-use std::iter::FromIterator;
+use std::{cell::Cell, iter::FromIterator};
+
+thread_local! {
+  static CLONES: Cell<usize> = Cell::new(0);
+  static DROPS: Cell<usize> = Cell::new(0);
+  static DEFAULTS: Cell<usize> = Cell::new(0);
+}
+
+/// Counts its clones.
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+struct Tracked(u32);
+impl Clone for Tracked {
+  fn clone(&self) -> Self {
+    CLONES.with(|c| c.set(c.get() + 1));
+    Tracked(self.0)
+  }
+}
+fn clones() -> usize {
+  CLONES.with(Cell::get)
+}
+
+/// Counts drops of non-zero (ie: non-default) values, and default values made.
+#[derive(Debug, PartialEq)]
+struct Droppy(u32);
+impl Default for Droppy {
+  fn default() -> Self {
+    DEFAULTS.with(|d| d.set(d.get() + 1));
+    Droppy(0)
+  }
+}
+impl Drop for Droppy {
+  fn drop(&mut self) {
+    if self.0 != 0 {
+      DROPS.with(|d| d.set(d.get() + 1));
+    }
+  }
+}
+fn drops() -> usize {
+  DROPS.with(Cell::get)
+}
+fn defaults() -> usize {
+  DEFAULTS.with(Cell::get)
+}
 
 #[test]
 fn test_a_vec() {
@@ -168,3 +211,2163 @@ fn ArrayVec_drain() {
   assert_eq!(Vec::from_iter(av.clone().drain(1..=1)), vec![2]);
   assert_eq!(Vec::from_iter(av.clone().drain(1..=2)), vec![2, 3]);
 }
+
+#[test]
+fn ArrayVec_drain_double_ended() {
+  let mut av = array_vec!([i32; 8], 1, 2, 3, 4, 5, 6);
+  let mut d = av.drain(1..5);
+  assert_eq!(d.len(), 4);
+  assert_eq!(d.next_back(), Some(5));
+  assert_eq!(d.next(), Some(2));
+  assert_eq!(d.len(), 2);
+  assert_eq!(d.next_back(), Some(4));
+  assert_eq!(d.next_back(), Some(3));
+  assert_eq!(d.next(), None);
+  assert_eq!(d.next_back(), None);
+  drop(d);
+  assert_eq!(av.as_slice(), &[1, 6][..]);
+
+  let mut av = array_vec!([i32; 8], 1, 2, 3, 4, 5, 6);
+  let rev: Vec<i32> = av.drain(2..).rev().collect();
+  assert_eq!(rev, vec![6, 5, 4, 3]);
+  assert_eq!(av.as_slice(), &[1, 2][..]);
+}
+
+#[test]
+fn ArrayVec_drain_dropped_early() {
+  let mut av: ArrayVec<[Droppy; 8]> = (1..=6).map(Droppy).collect();
+  let before = drops();
+  {
+    let mut d = av.drain(1..5);
+    assert_eq!(d.next().map(|x| x.0), Some(2));
+    assert_eq!(drops(), before + 1);
+    assert_eq!(d.next_back().map(|x| x.0), Some(5));
+  }
+  // 3 and 4 were never yielded, so the drain drops them
+  assert_eq!(drops(), before + 4);
+  assert_eq!(av.iter().map(|d| d.0).collect::<Vec<_>>(), vec![1, 6]);
+
+  // forgetting the drain leaks the tail but leaves a valid vec behind
+  let mut av = array_vec!([i32; 8], 1, 2, 3, 4, 5, 6);
+  core::mem::forget(av.drain(2..4));
+  assert_eq!(av.as_slice(), &[1, 2][..]);
+}
+
+#[test]
+fn ArrayVec_drain_consumer_panics() {
+  let mut av: ArrayVec<[Droppy; 8]> = (1..=7).map(Droppy).collect();
+  let before = drops();
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    for d in av.drain(1..6) {
+      if d.0 == 3 {
+        panic!("consumer gave up");
+      }
+    }
+  }));
+  assert!(result.is_err());
+  // all five drained items are dropped exactly once, and the tail moved down
+  assert_eq!(drops(), before + 5);
+  assert_eq!(av.iter().map(|d| d.0).collect::<Vec<_>>(), vec![1, 7]);
+  drop(av);
+  assert_eq!(drops(), before + 7);
+}
+
+#[test]
+fn ArrayVec_drain_large_range() {
+  let mut av: ArrayVec<[u32; 4096]> = (0..4096).collect();
+  let drained: Vec<u32> = av.drain(10..4000).collect();
+  assert!(drained.into_iter().eq(10..4000));
+  assert!(av.iter().cloned().eq((0..10).chain(4000..4096)));
+  av.set_len(av.capacity());
+  assert!(av[106..].iter().all(|&x| x == 0), "vacated slots are defaults");
+}
+
+#[test]
+fn ArrayVec_iterator_double_ended() {
+  let av = array_vec!([i32; 8], 1, 2, 3, 4, 5);
+  let mut it = av.into_iter();
+  assert_eq!(it.len(), 5);
+  assert_eq!(it.next_back(), Some(5));
+  assert_eq!(it.next(), Some(1));
+  assert_eq!(it.len(), 3);
+  assert_eq!(it.last(), Some(4));
+
+  let rev: Vec<i32> = av.into_iter().rev().collect();
+  assert_eq!(rev, vec![5, 4, 3, 2, 1]);
+
+  let mut it = av.into_iter();
+  assert_eq!(it.nth(3), Some(4));
+  assert_eq!(it.next_back(), Some(5));
+  assert_eq!(it.next_back(), None);
+  assert_eq!(it.next(), None);
+}
+
+#[test]
+fn ArrayVec_generic_without_default_bound() {
+  // None of these need an `A: Default` bound anymore, which matters for the
+  // array lengths that `core` doesn't give a `Default` impl to.
+  fn fresh<A: Array>() -> ArrayVec<A> {
+    ArrayVec::new()
+  }
+  fn split<A: Array>(av: &mut ArrayVec<A>, at: usize) -> ArrayVec<A> {
+    av.split_off(at)
+  }
+  fn collect<A: Array, I: IntoIterator<Item = A::Item>>(i: I) -> ArrayVec<A> {
+    i.into_iter().collect()
+  }
+  // code that still spells out the old bound keeps working
+  fn fresh_with_bound<A: Array + Default>() -> ArrayVec<A> {
+    ArrayVec::new()
+  }
+
+  let mut av: ArrayVec<[u8; 64]> = fresh();
+  av.extend(0..10);
+  let tail = split(&mut av, 4);
+  assert_eq!(av.as_slice(), &[0, 1, 2, 3][..]);
+  assert_eq!(tail.as_slice(), &[4, 5, 6, 7, 8, 9][..]);
+  let big: ArrayVec<[u16; 4096]> = collect(0..5);
+  assert_eq!(big.as_slice(), &[0, 1, 2, 3, 4][..]);
+  assert_eq!(big.capacity(), 4096);
+  let small: ArrayVec<[u8; 8]> = fresh_with_bound();
+  assert!(small.is_empty());
+}
+
+// `Array::default_array` mustn't be named `default`, or with the glob import
+// in scope these calls would be ambiguous with `Default::default`.
+#[test]
+fn Array_doesnt_shadow_Default() {
+  fn made<A: Array + Default>() -> A {
+    A::default()
+  }
+  let arr: [u8; 4] = made();
+  assert_eq!(arr, [0; 4]);
+  assert_eq!(<[u8; 4]>::default(), [0; 4]);
+}
+
+#[test]
+fn Array_default_array_is_all_defaults() {
+  let arr = <[String; 33] as Array>::default_array();
+  assert!(arr.iter().all(String::is_empty));
+  let arr = <[u32; 1024] as Array>::default_array();
+  assert!(arr.iter().all(|&x| x == 0));
+}
+
+#[test]
+fn array_vec_item_and_capacity_form() {
+  let av = array_vec!(u8; 16);
+  assert_eq!(av.capacity(), 16);
+  assert!(av.is_empty());
+
+  let av = array_vec!(u8; 16 => 1, 2, 3);
+  assert_eq!(av, array_vec!([u8; 16], 1, 2, 3));
+}
+
+#[cfg(feature = "rustc_1_55")]
+#[test]
+fn ArrayVecN_alias() {
+  struct Holder<const N: usize> {
+    words: ArrayVecN<u32, N>,
+  }
+  fn total<V: AsRef<[u32]>>(v: &V) -> u32 {
+    v.as_ref().iter().sum()
+  }
+
+  let mut h = Holder::<12> { words: ArrayVecN::<u32, 12>::new() };
+  h.words.extend(1..=12);
+  assert_eq!(h.words.len(), 12);
+  assert_eq!(total(&h.words), 78);
+
+  // lengths that aren't in the fixed list work too
+  let mut av: ArrayVecN<u8, 100> = array_vec!(u8; 100 => 7, 8);
+  av.push(9);
+  assert_eq!(av.as_slice(), &[7, 8, 9][..]);
+  assert_eq!(av.capacity(), 100);
+  let tail: ArrayVecN<u8, 100> = av.split_off(1);
+  assert_eq!(tail.as_slice(), &[8, 9][..]);
+}
+
+#[test]
+fn array_vec_repeat_form() {
+  let av = array_vec!([u8; 8] => 5; 0);
+  assert!(av.is_empty());
+
+  let av = array_vec!([u8; 8] => 5; 8);
+  assert_eq!(av.as_slice(), &[5; 8][..]);
+
+  let mut evaluated = 0;
+  let before = clones();
+  let av = array_vec!([Tracked; 8] => {
+    evaluated += 1;
+    Tracked(3)
+  }; 5);
+  assert_eq!(evaluated, 1);
+  // the last slot gets the original value
+  assert_eq!(clones() - before, 4);
+  assert_eq!(av.len(), 5);
+  assert!(av.iter().all(|t| t.0 == 3));
+}
+
+#[test]
+#[should_panic]
+fn array_vec_repeat_form_overflow() {
+  let _ = array_vec!([u8; 4] => 1; 5);
+}
+
+#[test]
+fn array_vec_exactly_at_capacity() {
+  // (more elements than this is a compile error, see the macro's docs)
+  let av = array_vec!([i32; 3], 1, 2, 3);
+  assert_eq!(av.len(), av.capacity());
+  assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+
+  fn generic<T: Clone + Default>(t: T) -> ArrayVec<[T; 2]> {
+    array_vec!([T; 2], t.clone(), t)
+  }
+  assert_eq!(generic(5_u8).as_slice(), &[5, 5][..]);
+}
+
+static STATIC_AV: ArrayVec<[u8; 64]> = ArrayVec::from_array_empty([0; 64]);
+const CONST_AV: ArrayVec<[&str; 4]> = ArrayVec::from_array_empty([""; 4]);
+
+#[test]
+fn ArrayVec_const_construction() {
+  assert!(STATIC_AV.is_empty());
+  assert_eq!(STATIC_AV.len(), 0);
+  assert_eq!(STATIC_AV.capacity(), 64);
+  let mut copied = STATIC_AV;
+  copied.push(3);
+  assert_eq!(copied.as_slice(), &[3][..]);
+
+  let mut strings = CONST_AV;
+  strings.push("hello");
+  assert_eq!(strings.as_slice(), &["hello"][..]);
+  assert!(CONST_AV.is_empty());
+}
+
+#[cfg(feature = "rustc_1_61")]
+#[test]
+fn ArrayVec_const_from_array_len() {
+  const TABLE: ArrayVec<[u16; 6]> =
+    ArrayVec::from_array_len([10, 20, 30, 0, 0, 0], 3);
+  static TABLE_STATIC: ArrayVec<[u16; 6]> = TABLE;
+  assert_eq!(TABLE.as_slice(), &[10, 20, 30][..]);
+  assert_eq!(TABLE_STATIC.len(), 3);
+  let mut t = TABLE;
+  t.push(40);
+  assert_eq!(t.as_slice(), &[10, 20, 30, 40][..]);
+}
+
+#[cfg(feature = "rustc_1_61")]
+#[test]
+#[should_panic]
+fn ArrayVec_const_from_array_len_overflow() {
+  let len = 5;
+  let _ = ArrayVec::from_array_len([1_u8, 2, 3, 4], len);
+}
+
+#[test]
+fn array_vec_matches_push_construction() {
+  fn pushed(items: &[i32]) -> ArrayVec<[i32; 4]> {
+    let mut av = ArrayVec::new();
+    for &i in items {
+      av.push(i);
+    }
+    av
+  }
+  fn whole_array(mut av: ArrayVec<[i32; 4]>) -> [i32; 4] {
+    // the spare slots must hold defaults either way
+    av.set_len(4);
+    [av[0], av[1], av[2], av[3]]
+  }
+
+  let empty = array_vec!([i32; 4]);
+  assert_eq!(empty, pushed(&[]));
+  assert_eq!(whole_array(empty), whole_array(pushed(&[])));
+
+  let partial = array_vec!([i32; 4], 1, 2);
+  assert_eq!(partial, pushed(&[1, 2]));
+  assert_eq!(whole_array(partial), whole_array(pushed(&[1, 2])));
+
+  let full = array_vec!([i32; 4], 1, 2, 3, 4);
+  assert_eq!(full, pushed(&[1, 2, 3, 4]));
+  assert_eq!(whole_array(full), whole_array(pushed(&[1, 2, 3, 4])));
+
+  // elements are still evaluated in order, once each
+  let mut log = Vec::new();
+  let av = array_vec!([String; 4], {
+    log.push(1);
+    "a".to_string()
+  }, {
+    log.push(2);
+    "b".to_string()
+  });
+  assert_eq!(log, vec![1, 2]);
+  assert_eq!(av.as_slice(), &["a".to_string(), "b".to_string()][..]);
+}
+
+#[cfg(feature = "unchecked_ops")]
+#[test]
+#[allow(unsafe_code)]
+fn ArrayVec_unchecked_ops_at_capacity() {
+  let mut av: ArrayVec<[String; 4]> = ArrayVec::new();
+  for s in &["a", "b", "c", "d"] {
+    unsafe { av.push_unchecked(s.to_string()) };
+  }
+  assert_eq!(av.len(), av.capacity());
+  assert_eq!(av.as_slice(), &["a", "b", "c", "d"][..]);
+
+  let mut av: ArrayVec<[u8; 5]> = array_vec!([u8; 5], 1);
+  unsafe { av.extend_from_slice_unchecked(&[2, 3, 4, 5]) };
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4, 5][..]);
+  unsafe { av.extend_from_slice_unchecked(&[]) };
+  assert_eq!(av.len(), 5);
+
+  unsafe { av.set_len_unchecked(0) };
+  assert!(av.is_empty());
+  unsafe { av.set_len_unchecked(5) };
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4, 5][..]);
+}
+
+/// Runs `f`, which must panic, and checks that the panic is reported at
+/// `line` of this file. Returns the panic message.
+#[cfg(feature = "rustc_1_46")]
+fn panic_reported_at<F: FnOnce()>(line: u32, f: F) -> String {
+  use std::{
+    panic::{catch_unwind, set_hook, take_hook, AssertUnwindSafe},
+    sync::Once,
+  };
+  thread_local! {
+    static EXPECTING: Cell<bool> = Cell::new(false);
+    static LAST_PANIC: Cell<Option<(String, u32, String)>> = Cell::new(None);
+  }
+  static INSTALL: Once = Once::new();
+  INSTALL.call_once(|| {
+    let default_hook = take_hook();
+    set_hook(Box::new(move |info| {
+      if !EXPECTING.with(Cell::get) {
+        return default_hook(info);
+      }
+      let location = info.location().unwrap();
+      let message = match info.payload().downcast_ref::<String>() {
+        Some(s) => s.clone(),
+        None => info.payload().downcast_ref::<&str>().unwrap().to_string(),
+      };
+      let last =
+        Some((location.file().to_string(), location.line(), message));
+      LAST_PANIC.with(|p| p.set(last));
+    }))
+  });
+  EXPECTING.with(|e| e.set(true));
+  let result = catch_unwind(AssertUnwindSafe(f));
+  EXPECTING.with(|e| e.set(false));
+  assert!(result.is_err(), "expected a panic");
+  let (file, panic_line, message) =
+    LAST_PANIC.with(Cell::take).expect("no panic recorded");
+  assert_eq!((file.as_str(), panic_line), (file!(), line), "{}", message);
+  message
+}
+
+#[cfg(feature = "rustc_1_46")]
+#[test]
+fn ArrayVec_panics_report_caller_location() {
+  let mut av = array_vec!([i32; 2], 1, 2);
+
+  let msg = panic_reported_at(line!(), || av.push(3));
+  assert_eq!(msg, "ArrayVec::push> capacity overflow (len 2, capacity 2)");
+  let msg = panic_reported_at(line!(), || av.insert(0, 3));
+  assert_eq!(msg, "ArrayVec::insert> capacity overflow (len 2, capacity 2)");
+  assert_eq!(av.as_slice(), &[1, 2][..]);
+
+  let msg = panic_reported_at(line!() + 1, || {
+    av.remove(2);
+  });
+  assert_eq!(
+    msg,
+    "ArrayVec::remove> index 2 is out of bounds (len 2, capacity 2)"
+  );
+  let msg = panic_reported_at(line!() + 1, || {
+    av.swap_remove(5);
+  });
+  assert_eq!(
+    msg,
+    "ArrayVec::swap_remove> index 5 is out of bounds (len 2, capacity 2)"
+  );
+  let msg = panic_reported_at(line!() + 1, || {
+    av.drain(1..3);
+  });
+  assert_eq!(
+    msg,
+    "ArrayVec::drain> range end 3 is out of bounds (len 2, capacity 2)"
+  );
+  let msg = panic_reported_at(line!(), || av.set_len(3));
+  assert_eq!(
+    msg,
+    "ArrayVec::set_len> length 3 exceeds capacity (len 2, capacity 2)"
+  );
+  let msg = panic_reported_at(line!() + 1, || {
+    av.split_off(3);
+  });
+  assert_eq!(
+    msg,
+    "ArrayVec::split_off> index 3 is out of bounds (len 2, capacity 2)"
+  );
+  panic_reported_at(line!() + 1, || {
+    let _ = ArrayVec::from_array_len([0_u8; 2], 3);
+  });
+
+  av.pop();
+  let msg = panic_reported_at(line!(), || av.insert(2, 3));
+  assert_eq!(
+    msg,
+    "ArrayVec::insert> index 2 is out of bounds (len 1, capacity 2)"
+  );
+  panic_reported_at(line!() + 1, || {
+    let _ = av[1];
+  });
+  panic_reported_at(line!(), || av[1] = 0);
+  assert_eq!(av.as_slice(), &[1][..]);
+}
+
+/// What C code would see as `struct { size_t len; uint32_t data[8]; }`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CArrayVec {
+  len: usize,
+  data: [u32; 8],
+}
+
+#[allow(unsafe_code)]
+fn bytes_of<T: Copy>(t: &T) -> Vec<u8> {
+  // Neither of the types used here has any padding, so all bytes are init.
+  let p = t as *const T as *const u8;
+  unsafe { std::slice::from_raw_parts(p, std::mem::size_of::<T>()) }.to_vec()
+}
+
+#[test]
+fn ArrayVec_layout_matches_c() {
+  use std::mem::{align_of, size_of};
+  assert_eq!(size_of::<ArrayVec<[u32; 8]>>(), size_of::<CArrayVec>());
+  assert_eq!(align_of::<ArrayVec<[u32; 8]>>(), align_of::<CArrayVec>());
+
+  let mut av: ArrayVec<[u32; 8]> = array_vec!([u32; 8], 1, 2, 3);
+  let base = &av as *const _ as usize;
+  assert_eq!(av.as_mut_ptr() as usize - base, size_of::<usize>());
+
+  let c = CArrayVec { len: 3, data: [1, 2, 3, 0, 0, 0, 0, 0] };
+  assert_eq!(bytes_of(&av), bytes_of(&c));
+}
+
+#[test]
+fn ArrayVec_raw_parts_round_trip() {
+  let av = array_vec!([u32; 8], 5, 6, 7);
+  let (data, len) = av.into_raw_parts();
+  let mut c = CArrayVec { len, data };
+
+  // pretend that the C side pushed an element
+  c.data[c.len] = 8;
+  c.len += 1;
+
+  let av: ArrayVec<[u32; 8]> = ArrayVec::from_raw_parts(c.data, c.len);
+  assert_eq!(av.as_slice(), &[5, 6, 7, 8][..]);
+  assert_eq!(bytes_of(&av), bytes_of(&c));
+
+  let (data, len) = av.into_raw_parts();
+  assert_eq!(len, 4);
+  assert_eq!(data, [5, 6, 7, 8, 0, 0, 0, 0]);
+}
+
+#[test]
+fn ArrayVec_raw_parts_round_trip_drops_once() {
+  let before = drops();
+  let mut av: ArrayVec<[Droppy; 4]> = (1..=3).map(Droppy).collect();
+  av.set_len(2);
+  let (data, len) = av.into_raw_parts();
+  assert_eq!(len, 2);
+  assert!(data.iter().map(|d| d.0).eq(vec![1, 2, 3, 0]));
+  let av = ArrayVec::from_raw_parts(data, len);
+  assert_eq!(drops(), before);
+  assert!(av.iter().map(|d| d.0).eq(1..=2));
+  // the whole array is dropped together, inactive slots included
+  drop(av);
+  assert_eq!(drops(), before + 3);
+}
+
+#[test]
+#[should_panic]
+fn ArrayVec_from_raw_parts_overflow() {
+  let _: ArrayVec<[u32; 2]> = ArrayVec::from_raw_parts([0; 2], 3);
+}
+
+// `append` used to be `for item in other.drain(..) { self.push(item) }`, and
+// with the remove-based drain that's O(n^2) moves: appending these 1000
+// elements took around half a million element moves, where now it's 1000.
+#[test]
+fn ArrayVec_append_large() {
+  let mut av: ArrayVec<[u32; 2048]> = (0..500).collect();
+  let mut av2: ArrayVec<[u32; 2048]> = (500..1500).collect();
+  av.append(&mut av2);
+  assert_eq!(av.len(), 1500);
+  assert!(av.iter().cloned().eq(0..1500));
+  assert!(av2.is_empty());
+  av2.set_len(1000);
+  assert!(av2.iter().all(|&x| x == 0), "vacated slots are defaulted");
+}
+
+#[test]
+fn ArrayVec_append_drops() {
+  let before = drops();
+  let mut av: ArrayVec<[Droppy; 8]> = array_vec!([Droppy; 8], Droppy(1));
+  let mut av2: ArrayVec<[Droppy; 8]> =
+    array_vec!([Droppy; 8], Droppy(2), Droppy(3));
+  av.append(&mut av2);
+  assert_eq!(drops(), before, "nothing is dropped while moving");
+  assert!(av.iter().map(|d| d.0).eq(1..=3));
+  drop(av2);
+  assert_eq!(drops(), before);
+  drop(av);
+  assert_eq!(drops(), before + 3);
+}
+
+#[test]
+fn ArrayVec_append_exactly_full_and_empty() {
+  let mut av = array_vec!([i32; 5], 1, 2);
+  let mut av2 = array_vec!([i32; 5], 3, 4, 5);
+  av.append(&mut av2);
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4, 5][..]);
+  assert!(av2.is_empty());
+
+  // appending nothing works even when full
+  av.append(&mut av2);
+  assert_eq!(av.len(), 5);
+  let mut empty = ArrayVec::new();
+  empty.append(&mut av);
+  assert_eq!(empty.as_slice(), &[1, 2, 3, 4, 5][..]);
+  assert!(av.is_empty());
+}
+
+#[test]
+fn ArrayVec_append_clones_nothing() {
+  let mut av: ArrayVec<[Tracked; 8]> = (1..=2).map(Tracked).collect();
+  let mut av2: ArrayVec<[Tracked; 8]> = (3..=6).map(Tracked).collect();
+  let before = clones();
+  av.append(&mut av2);
+  assert_eq!(clones(), before);
+  assert!(av.iter().map(|t| t.0).eq(1..=6));
+}
+
+#[test]
+fn ArrayVec_append_overflow_leaves_both_unchanged() {
+  let mut av = array_vec!([i32; 4], 1, 2, 3);
+  let mut av2 = array_vec!([i32; 4], 4, 5);
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    av.append(&mut av2);
+  }));
+  assert!(result.is_err());
+  assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+  assert_eq!(av2.as_slice(), &[4, 5][..]);
+}
+
+#[test]
+fn ArrayVec_try_append() {
+  let before = drops();
+  let mut av: ArrayVec<[Droppy; 4]> = (1..=2).map(Droppy).collect();
+  let mut av2: ArrayVec<[Droppy; 8]> = (3..=5).map(Droppy).collect();
+  let mut av2_small: ArrayVec<[Droppy; 4]> = av2.drain(..).collect();
+
+  // too many: both are untouched and nothing is dropped
+  assert_eq!(
+    av.try_append(&mut av2_small),
+    Err(CapacityError::new((), 5, 4))
+  );
+  assert_eq!(drops(), before);
+  assert!(av.iter().map(|d| d.0).eq(1..=2));
+  assert!(av2_small.iter().map(|d| d.0).eq(3..=5));
+
+  // exactly fills the remaining room
+  av2_small.pop();
+  assert_eq!(drops(), before + 1);
+  assert_eq!(av.try_append(&mut av2_small), Ok(()));
+  assert_eq!(drops(), before + 1);
+  assert!(av.iter().map(|d| d.0).eq(1..=4));
+  assert!(av2_small.is_empty());
+
+  // an empty `other` always fits, even into a full vec
+  assert_eq!(av.try_append(&mut av2_small), Ok(()));
+  assert_eq!(av.len(), 4);
+
+  drop(av2);
+  drop(av2_small);
+  assert_eq!(drops(), before + 1);
+  drop(av);
+  assert_eq!(drops(), before + 5);
+}
+
+#[test]
+fn ArrayVec_splice() {
+  // shorter replacement
+  let mut av = array_vec!([i32; 8], 1, 2, 3, 4, 5);
+  let removed: Vec<i32> = av.splice(1..4, vec![9]).collect();
+  assert_eq!(removed, vec![2, 3, 4]);
+  assert_eq!(av.as_slice(), &[1, 9, 5][..]);
+
+  // longer replacement that fits exactly
+  let mut av = array_vec!([i32; 8], 1, 2, 3, 4, 5);
+  av.splice(2..3, vec![7, 8, 9, 10]);
+  assert_eq!(av.as_slice(), &[1, 2, 7, 8, 9, 10, 4, 5][..]);
+
+  // equal length, and taking the removed items from the back
+  let mut av = array_vec!([i32; 8], 1, 2, 3, 4);
+  let removed: Vec<i32> = av.splice(1..3, vec![5, 6]).rev().collect();
+  assert_eq!(removed, vec![3, 2]);
+  assert_eq!(av.as_slice(), &[1, 5, 6, 4][..]);
+
+  // the vec matches `Vec::splice` even when the items aren't taken out
+  let mut av = array_vec!([i32; 8], 1, 2, 3, 4);
+  let mut v = av.to_vec();
+  drop(av.splice(.., 10..12));
+  drop(v.splice(.., 10..12));
+  assert_eq!(av.as_slice(), v.as_slice());
+}
+
+#[test]
+fn ArrayVec_splice_drops() {
+  let mut av: ArrayVec<[Droppy; 8]> = (1..=5).map(Droppy).collect();
+  let before = drops();
+  let mut splice = av.splice(1..4, (6..=7).map(Droppy));
+  assert_eq!(splice.next().map(|d| d.0), Some(2));
+  assert_eq!(drops(), before + 1);
+  drop(splice);
+  // 3 and 4 were dropped with the splice
+  assert_eq!(drops(), before + 3);
+  assert!(av.iter().map(|d| d.0).eq(vec![1, 6, 7, 5]));
+}
+
+#[test]
+fn ArrayVec_splice_forget() {
+  let mut av = array_vec!([i32; 8], 1, 2, 3, 4);
+  let mut splice = av.splice(1..3, vec![8, 9]);
+  assert_eq!(splice.next(), Some(2));
+  core::mem::forget(splice);
+  assert_eq!(av.as_slice(), &[1, 0, 3, 4][..]);
+  av.push(5);
+  assert_eq!(av.as_slice(), &[1, 0, 3, 4, 5][..]);
+}
+
+#[test]
+fn ArrayVec_splice_overflow() {
+  let mut av = array_vec!([i32; 4], 1, 2, 3);
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    av.splice(1..2, vec![7, 8, 9]);
+  }));
+  assert!(result.is_err());
+  // the replacements that fit are in place, in order
+  assert_eq!(av.as_slice(), &[1, 7, 8, 3][..]);
+}
+
+#[test]
+#[should_panic]
+fn ArrayVec_splice_out_of_bounds() {
+  let mut av = array_vec!([i32; 4], 1, 2);
+  av.splice(1..3, None);
+}
+
+#[test]
+fn ArrayVec_flatten_into() {
+  let frames = array_vec!([[u8; 4]; 4], [1, 2, 3, 4], [5, 6, 7, 8]);
+  let flat: ArrayVec<[u8; 16]> = frames.flatten_into();
+  assert_eq!(flat.as_slice(), &[1, 2, 3, 4, 5, 6, 7, 8][..]);
+
+  let exact: ArrayVec<[u8; 8]> =
+    array_vec!([[u8; 4]; 2], [1, 2, 3, 4], [5, 6, 7, 8]).flatten_into();
+  assert_eq!(exact.len(), 8);
+
+  let empty: ArrayVec<[[u8; 4]; 4]> = ArrayVec::new();
+  let flat: ArrayVec<[u8; 2]> = empty.flatten_into();
+  assert!(flat.is_empty());
+
+  let zero_width = array_vec!([[u8; 0]; 4], [], [], []);
+  let flat: ArrayVec<[u8; 0]> = zero_width.flatten_into();
+  assert!(flat.is_empty());
+}
+
+#[test]
+fn ArrayVec_flatten_into_moves() {
+  let frames = array_vec!(
+    [[Tracked; 2]; 3],
+    [Tracked(1), Tracked(2)],
+    [Tracked(3), Tracked(4)]
+  );
+  let before = clones();
+  let flat: ArrayVec<[Tracked; 8]> = frames.flatten_into();
+  assert_eq!(clones(), before);
+  assert!(flat.iter().map(|t| t.0).eq(1..=4));
+}
+
+#[test]
+#[should_panic]
+fn ArrayVec_flatten_into_overflow() {
+  let frames = array_vec!([[u8; 4]; 4], [1, 2, 3, 4], [5, 6, 7, 8]);
+  let _: ArrayVec<[u8; 7]> = frames.flatten_into();
+}
+
+#[test]
+fn ArrayVec_map() {
+  let av = array_vec!([u16; 8], 1, 2, 300);
+  let mut calls = ArrayVec::<[u16; 8]>::new();
+  let mapped: ArrayVec<[u32; 8]> = av.map(|x| {
+    calls.push(x);
+    u32::from(x) * 1000
+  });
+  assert_eq!(mapped.as_slice(), &[1000, 2000, 300_000][..]);
+  assert_eq!(calls.as_slice(), &[1, 2, 300][..]);
+
+  let shrunk: ArrayVec<[u8; 3]> = array_vec!([u8; 16], 1, 2).map(|x| x + 1);
+  assert_eq!(shrunk.as_slice(), &[2, 3][..]);
+
+  let empty: ArrayVec<[String; 2]> =
+    ArrayVec::<[i32; 4]>::new().map(|_| -> String { unreachable!() });
+  assert!(empty.is_empty());
+}
+
+#[test]
+fn ArrayVec_try_map() {
+  let av = array_vec!([i32; 8], 1, 2, -3, 4, 5);
+  let mut calls = 0;
+  let res: Result<ArrayVec<[u32; 8]>, i32> = av.try_map(|x| {
+    calls += 1;
+    if x < 0 {
+      Err(x)
+    } else {
+      Ok(x as u32)
+    }
+  });
+  assert_eq!(res, Err(-3));
+  assert_eq!(calls, 3);
+
+  let av: ArrayVec<[Droppy; 4]> = (1..=4).map(Droppy).collect();
+  let before = drops();
+  let res: Result<ArrayVec<[u32; 4]>, ()> =
+    av.try_map(|d| if d.0 == 2 { Err(()) } else { Ok(d.0) });
+  assert!(res.is_err());
+  assert_eq!(drops(), before + 4);
+
+  let empty: ArrayVec<[i32; 2]> = ArrayVec::new();
+  let res: Result<ArrayVec<[u8; 1]>, ()> = empty.try_map(|_| unreachable!());
+  assert_eq!(res.map(|av| av.len()), Ok(0));
+}
+
+#[test]
+#[should_panic]
+fn ArrayVec_map_too_long() {
+  let _: ArrayVec<[u8; 2]> = array_vec!([u8; 4], 1, 2, 3).map(|x| x);
+}
+
+#[test]
+fn ArrayVec_repeat_into() {
+  let pattern = array_vec!([u8; 4], 1, 2, 3);
+  let none: ArrayVec<[u8; 2]> = pattern.repeat_into(0);
+  assert!(none.is_empty());
+  let once: ArrayVec<[u8; 3]> = pattern.repeat_into(1);
+  assert_eq!(once, pattern.as_slice());
+  let many: ArrayVec<[u8; 16]> = pattern.repeat_into(4);
+  assert_eq!(many.as_slice(), &[1, 2, 3, 1, 2, 3, 1, 2, 3, 1, 2, 3][..]);
+  assert_eq!(many.as_slice(), &pattern.repeat(4)[..]);
+
+  let empty: ArrayVec<[u8; 4]> = ArrayVec::new();
+  let still_empty: ArrayVec<[u8; 1]> = empty.repeat_into(usize::MAX);
+  assert!(still_empty.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn ArrayVec_repeat_into_overflow() {
+  let pattern = array_vec!([u8; 4], 1, 2, 3);
+  let _: ArrayVec<[u8; 8]> = pattern.repeat_into(3);
+}
+
+#[test]
+#[should_panic]
+fn ArrayVec_repeat_into_mul_overflow() {
+  let pattern = array_vec!([u8; 4], 1, 2);
+  let _: ArrayVec<[u8; 8]> = pattern.repeat_into(usize::MAX);
+}
+
+#[test]
+fn ArrayVec_fill_spare_then_set_len() {
+  let mut av = array_vec!([u8; 8], 1, 2);
+  let (active, spare) = av.split_at_spare_mut();
+  assert_eq!(active, &[1, 2][..]);
+  assert_eq!(spare.len(), 6);
+  for (i, slot) in spare.iter_mut().take(3).enumerate() {
+    *slot = active[i % 2] * 10;
+  }
+  av.set_len(5);
+  assert_eq!(av.as_slice(), &[1, 2, 10, 20, 10][..]);
+
+  av.grab_spare_slice_mut()[0] = 99;
+  assert_eq!(av.grab_spare_slice(), &[99, 0, 0][..]);
+  av.set_len(6);
+  assert_eq!(av.as_slice(), &[1, 2, 10, 20, 10, 99][..]);
+
+  let (active, spare) = av.split_at_spare_mut();
+  assert_eq!((active.len(), spare.len()), (6, 2));
+}
+
+#[test]
+fn ArrayVec_split_off_back() {
+  let mut av = array_vec!([i32; 8], 1, 2, 3, 4, 5);
+  let back = av.split_off_back(0);
+  assert!(back.is_empty());
+  assert_eq!(av.len(), 5);
+
+  let back = av.split_off_back(2);
+  assert_eq!(back.as_slice(), &[4, 5][..]);
+  assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+
+  let back = av.split_off_back(3);
+  assert_eq!(back.as_slice(), &[1, 2, 3][..]);
+  assert!(av.is_empty());
+
+  let mut av: ArrayVec<[Droppy; 8]> = (1..=5).map(Droppy).collect();
+  let before = drops();
+  let back = av.split_off_back(2);
+  assert_eq!(drops(), before);
+  assert!(back.iter().map(|d| d.0).eq(4..=5));
+  drop(back);
+  assert_eq!(drops(), before + 2);
+  drop(av);
+  assert_eq!(drops(), before + 5);
+}
+
+#[test]
+#[should_panic]
+fn ArrayVec_split_off_back_too_many() {
+  let mut av = array_vec!([i32; 8], 1, 2);
+  let _ = av.split_off_back(3);
+}
+
+#[test]
+fn ArrayVec_split_off_moves() {
+  let mut av: ArrayVec<[Droppy; 8]> = (1..=5).map(Droppy).collect();
+  let (drops_before, defaults_before) = (drops(), defaults());
+  let av2 = av.split_off(2);
+  assert_eq!(drops(), drops_before);
+  // only the new vec's backing array is made, nothing per element
+  assert_eq!(defaults(), defaults_before + 8);
+  assert!(av.iter().map(|d| d.0).eq(1..=2));
+  assert!(av2.iter().map(|d| d.0).eq(3..=5));
+
+  let (data, len) = av.into_raw_parts();
+  assert_eq!(len, 2);
+  assert!(data[2..].iter().all(|d| d.0 == 0), "vacated slots are defaults");
+}
+
+#[test]
+fn ArrayVec_split_off_ends() {
+  let mut av = array_vec!([i32; 8], 1, 2, 3);
+  let rest = av.split_off(3);
+  assert!(rest.is_empty());
+  assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+
+  let all = av.split_off(0);
+  assert_eq!(all.as_slice(), &[1, 2, 3][..]);
+  assert!(av.is_empty());
+  av.set_len(3);
+  assert_eq!(av.as_slice(), &[0, 0, 0][..], "vacated slots are defaults");
+}
+
+#[test]
+#[should_panic(
+  expected = "ArrayVec::split_off> index 4 is out of bounds (len 3, capacity 8)"
+)]
+fn ArrayVec_split_off_out_of_bounds() {
+  let mut av = array_vec!([i32; 8], 1, 2, 3);
+  let _ = av.split_off(4);
+}
+
+// No `A: Default` bound needed, just `Array`.
+fn split_off_generic<A: Array>(av: &mut ArrayVec<A>) -> ArrayVec<A> {
+  av.split_off(av.len() / 2)
+}
+
+#[test]
+fn ArrayVec_split_off_generic() {
+  let mut av: ArrayVec<[u8; 64]> = (0..40).collect();
+  let back = split_off_generic(&mut av);
+  assert!(av.iter().cloned().eq(0..20));
+  assert!(back.iter().cloned().eq(20..40));
+}
+
+fn check_retain_matches_vec<A: Array<Item = u32>>() {
+  let n = A::CAPACITY as u32;
+  let keep = |x: &u32| x % 7 == 3 || (x / 1000) % 2 == 1;
+  let mut av: ArrayVec<A> = (0..n).collect();
+  let mut v: Vec<u32> = (0..n).collect();
+  av.retain(keep);
+  v.retain(keep);
+  assert_eq!(av.as_slice(), v.as_slice());
+  av.set_len(A::CAPACITY);
+  assert!(av[v.len()..].iter().all(|&x| x == 0), "tail is defaulted");
+}
+
+#[test]
+fn ArrayVec_retain_matches_vec() {
+  check_retain_matches_vec::<[u32; 4096]>();
+  #[cfg(feature = "rustc_1_55")]
+  check_retain_matches_vec::<[u32; 10_000]>();
+}
+
+#[test]
+fn ArrayVec_append_from_other_capacity() {
+  let mut av = array_vec!([i32; 6], 1, 2);
+  let mut small = array_vec!([i32; 2], 3, 4);
+  av.append_from(&mut small);
+  assert!(small.is_empty());
+  let mut big = array_vec!([i32; 16], 5, 6);
+  av.append_from(&mut big);
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4, 5, 6][..]);
+  assert!(big.is_empty());
+
+  let mut empty: ArrayVec<[i32; 3]> = ArrayVec::new();
+  av.append_from(&mut empty);
+  assert_eq!(av.len(), 6);
+}
+
+#[test]
+#[should_panic]
+fn ArrayVec_append_from_overflow() {
+  let mut av = array_vec!([i32; 3], 1, 2);
+  let mut other = array_vec!([i32; 8], 3, 4);
+  av.append_from(&mut other);
+}
+
+#[test]
+fn ArrayVec_clear_spare() {
+  let mut av: ArrayVec<[u32; 8]> = ArrayVec::new();
+  av.extend(1..=6);
+  av.truncate(4);
+  assert_eq!(av.pop(), Some(4));
+  av.set_len(6);
+  assert_eq!(av.as_slice(), &[1, 2, 3, 0, 5, 6][..]);
+
+  av.truncate(2);
+  av.clear_spare();
+  av.set_len(8);
+  assert_eq!(av.as_slice(), &[1, 2, 0, 0, 0, 0, 0, 0][..]);
+
+  av.clear_spare();
+  assert_eq!(av.len(), 8);
+}
+
+#[test]
+fn ArrayVec_concat_into() {
+  let header = array_vec!([u8; 16], 1, 2, 3);
+  let payload = array_vec!([u8; 64], 4, 5);
+  let packet: ArrayVec<[u8; 128]> = header.concat_into(payload);
+  assert_eq!(packet.as_slice(), &[1, 2, 3, 4, 5][..]);
+
+  let full = array_vec!([u8; 2], 1, 2);
+  let also_full = array_vec!([u8; 2], 3, 4);
+  let exact: ArrayVec<[u8; 4]> = full.concat_into(also_full);
+  assert_eq!(exact.as_slice(), &[1, 2, 3, 4][..]);
+
+  let empty: ArrayVec<[u8; 2]> = ArrayVec::new();
+  let also_empty: ArrayVec<[u8; 3]> = ArrayVec::new();
+  let nothing: ArrayVec<[u8; 1]> = empty.concat_into(also_empty);
+  assert!(nothing.is_empty());
+}
+
+#[test]
+fn ArrayVec_concat_into_moves() {
+  let a: ArrayVec<[Tracked; 2]> = (1..=2).map(Tracked).collect();
+  let b: ArrayVec<[Tracked; 4]> = (3..=5).map(Tracked).collect();
+  let before = clones();
+  let c: ArrayVec<[Tracked; 8]> = a.concat_into(b);
+  assert_eq!(clones(), before);
+  assert!(c.iter().map(|t| t.0).eq(1..=5));
+
+  let a: ArrayVec<[Droppy; 2]> = (1..=2).map(Droppy).collect();
+  let b: ArrayVec<[Droppy; 2]> = (3..=4).map(Droppy).collect();
+  let before = drops();
+  let c: ArrayVec<[Droppy; 4]> = a.concat_into(b);
+  assert_eq!(drops(), before);
+  drop(c);
+  assert_eq!(drops(), before + 4);
+}
+
+#[test]
+#[should_panic]
+fn ArrayVec_concat_into_overflow() {
+  let a = array_vec!([u8; 4], 1, 2, 3);
+  let b = array_vec!([u8; 4], 4, 5);
+  let _: ArrayVec<[u8; 4]> = a.concat_into(b);
+}
+
+#[test]
+fn ArrayVec_dedup_unsorted() {
+  let mut av = array_vec!([i32; 10], 4, 1, 4, 2, 1, 3, 2, 4);
+  av.dedup_unsorted();
+  assert_eq!(av.as_slice(), &[4, 1, 2, 3][..]);
+
+  let mut av = array_vec!([i32; 10], 5, 3, 9, 1);
+  av.dedup_unsorted();
+  assert_eq!(av.as_slice(), &[5, 3, 9, 1][..]);
+
+  let mut av = array_vec!([i32; 10], 7, 7, 7, 7, 7);
+  av.dedup_unsorted();
+  assert_eq!(av.as_slice(), &[7][..]);
+
+  let mut av: ArrayVec<[i32; 4]> = ArrayVec::new();
+  av.dedup_unsorted();
+  assert!(av.is_empty());
+}
+
+#[test]
+fn ArrayVec_dedup_unsorted_by_key() {
+  let mut av = array_vec!([i32; 10], 10, 21, 12, 30, 41, 22);
+  av.dedup_unsorted_by_key(|x| x % 10);
+  assert_eq!(av.as_slice(), &[10, 21, 12][..]);
+
+  let mut av: ArrayVec<[Droppy; 8]> = (1..=6).map(Droppy).collect();
+  let before = drops();
+  av.dedup_unsorted_by_key(|d| d.0 % 2);
+  assert!(av.iter().map(|d| d.0).eq(vec![1, 2]));
+  assert_eq!(drops(), before + 4);
+}
+
+#[test]
+fn ArrayVec_dedup_matches_vec() {
+  let cases: &[&[i32]] = &[
+    &[],
+    &[1],
+    &[1, 1, 1, 2, 3],
+    &[1, 2, 2, 2, 3, 4, 4, 5],
+    &[1, 2, 3, 3, 3],
+    &[7, 7, 7, 7, 7, 7],
+    &[1, 1, 2, 1, 1, 2, 2],
+  ];
+  for case in cases {
+    let mut av: ArrayVec<[i32; 8]> = case.iter().copied().collect();
+    let mut v = case.to_vec();
+    av.dedup();
+    v.dedup();
+    assert_eq!(av.as_slice(), v.as_slice(), "{:?}", case);
+  }
+}
+
+#[test]
+fn ArrayVec_dedup_by_argument_order() {
+  let mut av = array_vec!([i32; 8], 1, 2, 4, 5, 9, 10, 11);
+  let mut calls = Vec::new();
+  av.dedup_by(|current, previous| {
+    calls.push((*current, *previous));
+    *current - *previous == 1
+  });
+  assert_eq!(av.as_slice(), &[1, 4, 9, 11][..]);
+  assert_eq!(calls, vec![(2, 1), (4, 1), (5, 4), (9, 4), (10, 9), (11, 9)]);
+
+  let mut v = vec![1, 2, 4, 5, 9, 10, 11];
+  let mut vec_calls = Vec::new();
+  v.dedup_by(|current, previous| {
+    vec_calls.push((*current, *previous));
+    *current - *previous == 1
+  });
+  assert_eq!(calls, vec_calls);
+  assert_eq!(av.as_slice(), v.as_slice());
+
+  let mut av = array_vec!([i32; 8], 10, 11, 20, 25, 31, 39);
+  av.dedup_by_key(|x| *x / 10);
+  assert_eq!(av.as_slice(), &[10, 20, 31][..]);
+}
+
+#[test]
+fn ArrayVec_dedup_drops_removed() {
+  let mut av: ArrayVec<[Droppy; 8]> =
+    [1, 1, 2, 2, 2, 3].iter().map(|&x| Droppy(x)).collect();
+  let before = drops();
+  av.dedup_by_key(|d| d.0);
+  assert_eq!(drops(), before + 3);
+  assert!(av.iter().map(|d| d.0).eq(1..=3));
+}
+
+#[test]
+fn ArrayVec_retain_calls_in_order() {
+  // a few hundred elements, with runs of kept and rejected ones of varying
+  // lengths, and the predicate sees each element once, front to back
+  let items: Vec<u32> = (0..300).collect();
+  let keep = |x: u32| (x / 7 + x / 3) % 3 < 2;
+  let mut av: ArrayVec<[u32; 512]> = items.iter().copied().collect();
+  let mut seen = Vec::new();
+  av.retain(|&x| {
+    seen.push(x);
+    keep(x)
+  });
+  assert_eq!(seen, items);
+  let expected: Vec<u32> = items.iter().copied().filter(|&x| keep(x)).collect();
+  assert_eq!(av.as_slice(), expected.as_slice());
+}
+
+#[test]
+fn ArrayVec_retain_mut_updates_and_removes() {
+  // (id, ttl) entries, each pass ages them and evicts the expired ones
+  let mut cache = array_vec!([(u32, u32); 8], (1, 2), (2, 1), (3, 3), (4, 1));
+  cache.retain_mut(|entry| {
+    entry.1 -= 1;
+    entry.1 > 0
+  });
+  assert_eq!(cache.as_slice(), &[(1, 1), (3, 2)][..]);
+  cache.retain_mut(|entry| {
+    entry.1 -= 1;
+    entry.1 > 0
+  });
+  assert_eq!(cache.as_slice(), &[(3, 1)][..]);
+}
+
+#[test]
+fn ArrayVec_retain_drops_each_rejected_once() {
+  let mut av: ArrayVec<[Droppy; 32]> = (1..=20).map(Droppy).collect();
+  let before = drops();
+  av.retain(|d| d.0 % 4 == 0);
+  assert_eq!(drops(), before + 15);
+  assert!(av.iter().map(|d| d.0).eq(vec![4, 8, 12, 16, 20]));
+
+  let before = drops();
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    av.retain(|d| if d.0 == 12 { panic!() } else { d.0 != 8 });
+  }));
+  assert!(result.is_err());
+  // only 8 was rejected before the panic, and the rest are all still owned
+  assert_eq!(drops(), before + 1);
+  drop(av);
+  assert_eq!(drops(), before + 5);
+}
+
+#[test]
+fn ArrayVec_truncate_drops_tail() {
+  let mut av: ArrayVec<[Droppy; 8]> = (1..=6).map(Droppy).collect();
+  let (drops_before, defaults_before) = (drops(), defaults());
+  av.truncate(6);
+  assert_eq!((drops(), defaults()), (drops_before, defaults_before));
+  av.truncate(10);
+  assert_eq!((drops(), defaults()), (drops_before, defaults_before));
+
+  av.truncate(4);
+  assert_eq!(drops(), drops_before + 2);
+  assert_eq!(defaults(), defaults_before + 2);
+  assert!(av.iter().map(|d| d.0).eq(1..=4));
+
+  av.clear();
+  assert!(av.is_empty());
+  assert_eq!(drops(), drops_before + 6);
+  av.set_len(8);
+  assert!(av.iter().all(|d| d.0 == 0), "the tail is defaulted");
+
+  let mut av = array_vec!([String; 4], "a".to_string(), "b".to_string());
+  av.truncate(1);
+  av.set_len(2);
+  assert_eq!(av.as_slice(), &["a".to_string(), String::new()][..]);
+
+  let mut av = array_vec!([i32; 4], 1, 2, 3);
+  av.truncate(1);
+  assert_eq!(av.as_slice(), &[1][..]);
+}
+
+/// A tiny xorshift generator, so the tests don't need a `rand` dependency.
+fn xorshift(state: &mut u32) -> u32 {
+  *state ^= *state << 13;
+  *state ^= *state >> 17;
+  *state ^= *state << 5;
+  *state
+}
+
+// `insert` used to move every shifted element through a temporary and then
+// `push` the last one. Inserting at the front of a nearly full 4096 element
+// vec is now a single `rotate_right` over the tail.
+#[test]
+fn ArrayVec_insert_front_of_nearly_full() {
+  let mut av: ArrayVec<[u32; 4096]> = (1..4096).collect();
+  av.insert(0, 0);
+  assert_eq!(av.len(), 4096);
+  assert!(av.iter().cloned().eq(0..4096));
+}
+
+#[test]
+fn ArrayVec_insert_front_repeatedly() {
+  let mut av: ArrayVec<[u32; 4096]> = ArrayVec::new();
+  for i in 0..4096 {
+    av.insert(0, i);
+  }
+  assert!(av.iter().cloned().eq((0..4096).rev()));
+}
+
+#[test]
+fn ArrayVec_insert_matches_vec() {
+  let mut state = 0x1234_5678;
+  let mut av: ArrayVec<[u32; 256]> = ArrayVec::new();
+  let mut v: Vec<u32> = Vec::new();
+  for i in 0..256 {
+    let index = xorshift(&mut state) as usize % (v.len() + 1);
+    av.insert(index, i);
+    v.insert(index, i);
+    assert_eq!(av.as_slice(), v.as_slice());
+  }
+}
+
+#[test]
+fn ArrayVec_insert_drops_nothing() {
+  let mut av: ArrayVec<[Droppy; 4]> = array_vec!([Droppy; 4], Droppy(1));
+  let before = drops();
+  av.insert(0, Droppy(2));
+  av.insert(1, Droppy(3));
+  av.insert(3, Droppy(4));
+  assert_eq!(drops(), before);
+  assert!(av.iter().map(|d| d.0).eq(vec![2, 3, 1, 4]));
+}
+
+#[test]
+fn ArrayVec_extend_from_within() {
+  // an LZ77 style run: distance 1, length 5
+  let mut av = array_vec!([u8; 16], b'x', b'a');
+  for _ in 0..5 {
+    let len = av.len();
+    av.extend_from_within(len - 1..len);
+  }
+  assert_eq!(av.as_slice(), b"xaaaaaa");
+
+  av.extend_from_within(0..0);
+  av.extend_from_within(3..3);
+  assert_eq!(av.as_slice(), b"xaaaaaa");
+
+  av.extend_from_within(..=1);
+  assert_eq!(av.as_slice(), b"xaaaaaaxa");
+
+  let mut v = av.to_vec();
+  av.extend_from_within(2..6);
+  v.extend_from_within(2..6);
+  assert_eq!(av.as_slice(), v.as_slice());
+}
+
+#[test]
+fn ArrayVec_extend_from_within_overflow() {
+  let mut av = array_vec!([i32; 5], 1, 2, 3);
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    av.extend_from_within(..);
+  }));
+  assert!(result.is_err());
+  assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+}
+
+#[test]
+#[should_panic]
+fn ArrayVec_extend_from_within_out_of_bounds() {
+  let mut av = array_vec!([i32; 8], 1, 2, 3);
+  av.extend_from_within(2..4);
+}
+
+#[test]
+fn ArrayVec_extend_from_slice_matches_vec() {
+  let mut av: ArrayVec<[u8; 64]> = ArrayVec::new();
+  let mut v: Vec<u8> = Vec::new();
+  for chunk in [&[1, 2, 3][..], &[], &[4; 20], &[5, 6]].iter() {
+    av.extend_from_slice(chunk);
+    v.extend_from_slice(chunk);
+    assert_eq!(av.as_slice(), v.as_slice());
+  }
+  let rest: Vec<u8> = (0..(64 - v.len()) as u8).collect();
+  av.extend_from_slice(&rest);
+  v.extend_from_slice(&rest);
+  assert_eq!(av.as_slice(), v.as_slice());
+}
+
+#[test]
+fn ArrayVec_extend_from_slice_overflow_leaves_vec_unchanged() {
+  let mut av = array_vec!([u8; 4], 1, 2);
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    av.extend_from_slice(&[3, 4, 5]);
+  }));
+  assert!(result.is_err());
+  assert_eq!(av.as_slice(), &[1, 2][..]);
+  assert_eq!(av.grab_spare_slice(), &[0, 0][..]);
+}
+
+#[test]
+fn ArrayVec_try_extend_from_slice() {
+  let mut av = array_vec!([i32; 6], 1);
+  assert_eq!(av.try_extend_from_slice(&[2, 3]), Ok(()));
+  assert_eq!(av.try_extend_from_slice(&[]), Ok(()));
+  assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+
+  assert_eq!(
+    av.try_extend_from_slice(&[4, 5, 6, 7]),
+    Err(CapacityError::new((), 7, 6))
+  );
+  assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+
+  assert_eq!(av.try_extend_from_slice(&[4, 5, 6]), Ok(()));
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4, 5, 6][..]);
+  assert_eq!(
+    av.try_extend_from_slice(&[7]),
+    Err(CapacityError::new((), 7, 6))
+  );
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+fn ArrayVec_try_extend_from_slice_failure_clones_nothing() {
+  let mut av: ArrayVec<[Tracked; 3]> = array_vec!([Tracked; 3], Tracked(1));
+  let extra = [Tracked(2), Tracked(3), Tracked(4)];
+  let before = clones();
+  assert!(av.try_extend_from_slice(&extra).is_err());
+  assert_eq!(clones(), before);
+  assert_eq!(av.as_slice(), &[Tracked(1)][..]);
+}
+
+#[test]
+fn ArrayVec_extend_by_taking() {
+  let mut av: ArrayVec<[Droppy; 4]> = array_vec!([Droppy; 4], Droppy(1));
+  let mut scratch = [Droppy(2), Droppy(3)];
+  let before = drops();
+  assert_eq!(av.extend_by_taking(&mut scratch), 2);
+  assert_eq!(drops(), before);
+  assert!(av.iter().map(|d| d.0).eq(1..=3));
+  assert!(scratch.iter().all(|d| d.0 == 0));
+
+  // stops when full, the rest stays in the source
+  let mut scratch = [Droppy(4), Droppy(5), Droppy(6)];
+  assert_eq!(av.extend_by_taking(&mut scratch), 1);
+  assert!(av.iter().map(|d| d.0).eq(1..=4));
+  assert!(scratch.iter().map(|d| d.0).eq(vec![0, 5, 6]));
+  assert_eq!(av.extend_by_taking(&mut scratch[1..]), 0);
+  assert!(scratch.iter().map(|d| d.0).eq(vec![0, 5, 6]));
+  assert_eq!(drops(), before);
+}
+
+#[test]
+fn ArrayVec_filled_with_and_from_elem() {
+  let mut names = vec!["c", "b", "a"];
+  let av: ArrayVec<[String; 3]> =
+    ArrayVec::filled_with(|| String::from(names.pop().unwrap()));
+  assert_eq!(av.len(), 3);
+  assert_eq!(av.as_slice(), &["a", "b", "c"][..]);
+
+  let av: ArrayVec<[Vec<u8>; 2]> = ArrayVec::from_elem(vec![1, 2]);
+  assert_eq!(av.as_slice(), &[vec![1, 2], vec![1, 2]][..]);
+
+  let av: ArrayVec<[String; 0]> = ArrayVec::filled_with(|| unreachable!());
+  assert!(av.is_empty());
+  let av: ArrayVec<[String; 0]> = ArrayVec::from_elem(String::from("x"));
+  assert!(av.is_empty());
+}
+
+#[test]
+fn ArrayVec_extract_if() {
+  let mut av: ArrayVec<[i32; 8]> = (1..=8).collect();
+  let mut v: Vec<i32> = (1..=8).collect();
+  let out: Vec<i32> = av.extract_if(|x| *x % 3 != 0).collect();
+  let vec_out: Vec<i32> = v.extract_if(.., |x| *x % 3 != 0).collect();
+  assert_eq!(out, vec_out);
+  assert_eq!(av.as_slice(), v.as_slice());
+  assert_eq!(av.as_slice(), &[3, 6][..]);
+
+  let mut av: ArrayVec<[i32; 8]> = ArrayVec::new();
+  assert_eq!(av.extract_if(|_| unreachable!()).next(), None);
+  assert!(av.is_empty());
+}
+
+#[test]
+fn ArrayVec_extract_if_partial() {
+  let mut av: ArrayVec<[i32; 8]> = (1..=8).collect();
+  let mut iter = av.extract_if(|x| *x % 2 == 0);
+  assert_eq!(iter.next(), Some(2));
+  assert_eq!(iter.next(), Some(4));
+  drop(iter);
+  // 5 and later were never visited, so they're all kept
+  assert_eq!(av.as_slice(), &[1, 3, 5, 6, 7, 8][..]);
+
+  let mut av: ArrayVec<[i32; 8]> = (1..=8).collect();
+  drop(av.extract_if(|_| true));
+  assert_eq!(av.len(), 8);
+}
+
+#[test]
+fn ArrayVec_extract_if_mutates_and_drops() {
+  let mut av: ArrayVec<[Droppy; 8]> = (1..=6).map(Droppy).collect();
+  let before = drops();
+  let taken: Vec<u32> = av
+    .extract_if(|d| {
+      d.0 *= 10;
+      d.0 > 30
+    })
+    .map(|d| d.0)
+    .collect();
+  assert_eq!(taken, vec![40, 50, 60]);
+  assert_eq!(drops(), before + 3);
+  assert!(av.iter().map(|d| d.0).eq(vec![10, 20, 30]));
+  // the spare slots are all defaults, so nothing is dropped twice
+  av.set_len(8);
+  assert!(av[3..].iter().all(|d| d.0 == 0));
+  av.set_len(3);
+}
+
+#[test]
+fn ArrayVec_extract_if_panic_keeps_the_rest() {
+  let mut av: ArrayVec<[i32; 8]> = (1..=6).collect();
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    av.extract_if(|x| if *x == 4 { panic!() } else { *x % 2 == 1 })
+      .for_each(drop);
+  }));
+  assert!(result.is_err());
+  assert_eq!(av.as_slice(), &[2, 4, 5, 6][..]);
+}
+
+#[test]
+fn ArrayVec_fill_from_iter() {
+  let mut av: ArrayVec<[i32; 4]> = ArrayVec::new();
+  let rest = av.fill_from_iter(1..=3);
+  assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+  assert_eq!(rest.count(), 0);
+
+  let rest = av.fill_from_iter(vec![4, 5, 6, 7]);
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4][..]);
+  assert_eq!(rest.collect::<Vec<_>>(), vec![5, 6, 7]);
+}
+
+// `slice::fill` is from Rust 1.50, so this rides on the newest feature that
+// implies it.
+#[cfg(feature = "rustc_1_55")]
+#[test]
+fn ArrayVec_fill_is_the_slice_method() {
+  let mut av = array_vec!([i32; 4], 1, 2, 3);
+  av.fill(7);
+  assert_eq!(av.as_slice(), &[7, 7, 7][..]);
+}
+
+#[test]
+fn ArrayVec_fill_from_iter_full_consumes_nothing() {
+  let mut pulled = 0;
+  let mut av = array_vec!([i32; 2], 1, 2);
+  let mut rest = av.fill_from_iter((10..).inspect(|_| pulled += 1));
+  assert_eq!(rest.next(), Some(10));
+  drop(rest);
+  assert_eq!(pulled, 1);
+  assert_eq!(av.as_slice(), &[1, 2][..]);
+
+  // filling up to capacity doesn't pull one extra
+  let mut pulled = 0;
+  let mut av: ArrayVec<[i32; 3]> = ArrayVec::new();
+  let _ = av.fill_from_iter((10..).inspect(|_| pulled += 1));
+  assert_eq!(pulled, 3);
+  assert_eq!(av.as_slice(), &[10, 11, 12][..]);
+}
+
+#[test]
+fn ArrayVec_try_remove() {
+  let mut av = array_vec!([i32; 4], 1, 2, 3);
+  assert_eq!(av.try_remove(3), None);
+  assert_eq!(av.try_remove(2), Some(3));
+  assert_eq!(av.try_remove(0), Some(1));
+  assert_eq!(av.as_slice(), &[2][..]);
+  assert_eq!(av.try_remove(0), Some(2));
+  assert_eq!(av.try_remove(0), None);
+  assert!(av.is_empty());
+}
+
+#[test]
+fn ArrayVec_try_swap_remove() {
+  let mut av = array_vec!([i32; 4], 1, 2, 3, 4);
+  assert_eq!(av.try_swap_remove(4), None);
+  assert_eq!(av.try_swap_remove(3), Some(4));
+  assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+  assert_eq!(av.try_swap_remove(0), Some(1));
+  assert_eq!(av.as_slice(), &[3, 2][..]);
+
+  let mut av: ArrayVec<[i32; 4]> = ArrayVec::new();
+  assert_eq!(av.try_swap_remove(0), None);
+}
+
+#[test]
+fn ArrayVec_try_resize() {
+  let mut av = array_vec!([i32; 4], 1, 2);
+  assert_eq!(av.try_resize(5, 9), Err(CapacityError::new((), 5, 4)));
+  assert_eq!(av.as_slice(), &[1, 2][..]);
+  assert_eq!(av.try_resize(4, 9), Ok(()));
+  assert_eq!(av.as_slice(), &[1, 2, 9, 9][..]);
+  assert_eq!(av.try_resize(1, 9), Ok(()));
+  assert_eq!(av.as_slice(), &[1][..]);
+}
+
+#[test]
+fn ArrayVec_try_resize_with() {
+  let mut calls = 0;
+  let mut av = array_vec!([i32; 4], 1, 2);
+  let result = av.try_resize_with(5, || {
+    calls += 1;
+    0
+  });
+  assert_eq!(result, Err(CapacityError::new((), 5, 4)));
+  assert_eq!(calls, 0);
+  assert_eq!(av.as_slice(), &[1, 2][..]);
+
+  let result = av.try_resize_with(4, || {
+    calls += 1;
+    calls
+  });
+  assert_eq!(result, Ok(()));
+  assert_eq!(av.as_slice(), &[1, 2, 1, 2][..]);
+  assert_eq!(av.try_resize_with(0, || unreachable!()), Ok(()));
+  assert!(av.is_empty());
+}
+
+#[test]
+fn CapacityError_display_and_element() {
+  let err = CapacityError::new((), 7, 4);
+  assert_eq!(
+    err.to_string(),
+    "insufficient capacity: needs room for 7 elements, but the capacity is 4"
+  );
+  assert_eq!((err.requested_len(), err.capacity()), (7, 4));
+
+  let data = [String::from("a"), String::from("b")];
+  let ptr = data[0].as_ptr();
+  let err = ArrayVec::from_array_len_checked(data, 3).unwrap_err();
+  assert_eq!((err.requested_len(), err.capacity()), (3, 2));
+  let data = err.element();
+  assert_eq!(data, ["a", "b"]);
+  assert_eq!(data[0].as_ptr(), ptr);
+
+  let av = ArrayVec::from_array_len_checked(data, 1).unwrap();
+  assert_eq!(av.as_slice(), &["a"][..]);
+
+  let err = CapacityError::new(5, 2, 1);
+  assert_eq!(err.without_element(), CapacityError::new((), 2, 1));
+  #[cfg(feature = "std")]
+  {
+    let boxed: Box<dyn std::error::Error> = Box::new(err);
+    assert!(boxed.to_string().contains("capacity is 1"));
+  }
+}
+
+#[test]
+fn IteratorExt_try_collect_arrayvec() {
+  let av = (1..=4).try_collect_arrayvec::<[i32; 4]>().unwrap();
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4][..]);
+
+  let av = core::iter::empty().try_collect_arrayvec::<[i32; 4]>().unwrap();
+  assert!(av.is_empty());
+
+  let mut iter = 1..=10;
+  let err = iter.by_ref().try_collect_arrayvec::<[i32; 4]>().unwrap_err();
+  assert_eq!(err.requested_len(), 10);
+  assert_eq!(err.capacity(), 4);
+  let (av, extra) = err.element();
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4][..]);
+  // only the one item that didn't fit was taken past the capacity, and it's
+  // handed back
+  assert_eq!(extra, 5);
+  assert_eq!(iter.next(), Some(6));
+
+  // without a size hint the requested length is just a lower bound
+  let mut n = 0;
+  let unhinted = core::iter::from_fn(|| {
+    n += 1;
+    if n <= 6 {
+      Some(n)
+    } else {
+      None
+    }
+  });
+  let err = unhinted.try_collect_arrayvec::<[i32; 2]>().unwrap_err();
+  assert_eq!(err.requested_len(), 3);
+  assert_eq!(err.element().1, 3);
+
+  // unbounded iterators have a lower bound of usize::MAX
+  let err = (0_u32..).try_collect_arrayvec::<[u32; 4]>().unwrap_err();
+  assert_eq!(err.requested_len(), usize::MAX);
+  let (av, extra) = err.element();
+  assert_eq!(av.as_slice(), &[0, 1, 2, 3][..]);
+  assert_eq!(extra, 4);
+  let err =
+    core::iter::repeat(1_u8).try_collect_arrayvec::<[u8; 4]>().unwrap_err();
+  assert_eq!(err.requested_len(), usize::MAX);
+}
+
+#[test]
+fn ArrayVec_as_inner_mut_past_len_is_not_part_of_the_vec() {
+  let mut av = array_vec!([i32; 6], 1, 2, 3);
+  let other = av;
+  for slot in &mut av.as_inner_mut()[3..] {
+    *slot = 7;
+  }
+  assert_eq!(av, other);
+  assert!(av.iter().eq(&[1, 2, 3]));
+  assert_eq!(av.len(), 3);
+  assert_eq!(av.as_inner(), &[1, 2, 3, 7, 7, 7]);
+
+  av.as_inner_mut()[0] = 5;
+  assert_eq!(av.as_slice(), &[5, 2, 3][..]);
+}
+
+#[test]
+fn ArrayVec_try_from_slice() {
+  use std::convert::TryFrom;
+
+  let av = ArrayVec::<[i32; 4]>::try_from(&[1, 2, 3, 4][..]).unwrap();
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4][..]);
+
+  let av = ArrayVec::<[i32; 4]>::try_from(&[][..]).unwrap();
+  assert!(av.is_empty());
+
+  let items = [Tracked(1), Tracked(2), Tracked(3)];
+  let before = clones();
+  let err = ArrayVec::<[Tracked; 2]>::try_from(&items[..]).unwrap_err();
+  assert_eq!(err, CapacityError::new((), 3, 2));
+  assert_eq!(clones(), before);
+
+  let av = ArrayVec::<[Tracked; 3]>::try_from(&items[..]).unwrap();
+  assert_eq!(clones(), before + 3);
+  assert_eq!(av.as_slice(), &items[..]);
+}
+
+#[cfg(feature = "rustc_1_55")]
+#[test]
+fn ArrayVec_from_partial_array() {
+  let av: ArrayVec<[u8; 4]> = ArrayVec::from_partial_array([]);
+  assert!(av.is_empty());
+  assert_eq!(av.into_inner(), [0; 4]);
+
+  let av: ArrayVec<[u8; 4]> = ArrayVec::from_partial_array([5, 6]);
+  assert_eq!(av.as_slice(), &[5, 6][..]);
+  assert_eq!(av.into_inner(), [5, 6, 0, 0]);
+
+  let av: ArrayVec<[String; 3]> =
+    ArrayVec::from_partial_array([String::from("a"), String::from("b")]);
+  assert_eq!(av.as_slice(), &["a", "b"][..]);
+
+  let av: ArrayVec<[u8; 3]> = ArrayVec::from_partial_array([1, 2, 3]);
+  assert_eq!(av, ArrayVec::from([1, 2, 3]));
+  assert_eq!(av.len(), 3);
+}
+
+#[cfg(feature = "rustc_1_55")]
+#[test]
+#[should_panic]
+fn ArrayVec_from_partial_array_too_long() {
+  let _: ArrayVec<[u8; 2]> = ArrayVec::from_partial_array([1, 2, 3]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn ArrayVec_vec_conversions() {
+  use std::convert::TryFrom;
+
+  let av = array_vec!([i32; 8], 1, 2, 3);
+  let v: Vec<i32> = av.into();
+  assert_eq!(v, vec![1, 2, 3]);
+  assert_eq!(v.capacity(), 3);
+
+  let av = ArrayVec::<[i32; 4]>::try_from(vec![4, 5, 6, 7]).unwrap();
+  assert_eq!(av.as_slice(), &[4, 5, 6, 7][..]);
+
+  let v = vec![1, 2, 3, 4, 5];
+  let ptr = v.as_ptr();
+  let err = ArrayVec::<[i32; 4]>::try_from(v).unwrap_err();
+  assert_eq!((err.requested_len(), err.capacity()), (5, 4));
+  let v = err.element();
+  assert_eq!(v.as_ptr(), ptr);
+  assert_eq!(v, vec![1, 2, 3, 4, 5]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn ArrayVec_vec_conversions_move() {
+  use std::convert::TryFrom;
+
+  let before = drops();
+  let v: Vec<Droppy> = (1..=3).map(Droppy).collect();
+  let av = ArrayVec::<[Droppy; 4]>::try_from(v).unwrap();
+  assert_eq!(drops(), before);
+  assert!(av.iter().map(|d| d.0).eq(1..=3));
+
+  let v: Vec<Droppy> = av.into();
+  assert_eq!(drops(), before);
+  assert!(v.iter().map(|d| d.0).eq(1..=3));
+
+  let err = ArrayVec::<[Droppy; 2]>::try_from(v).unwrap_err();
+  assert_eq!(drops(), before);
+  drop(err);
+  assert_eq!(drops(), before + 3);
+}
+
+#[test]
+fn ArrayVec_into_array() {
+  let av = array_vec!([i32; 4], 1, 2, 3, 4);
+  assert_eq!(av.into_array(), Ok([1, 2, 3, 4]));
+
+  let av = array_vec!([String; 3], String::from("a"), String::from("b"));
+  let av = av.into_array().unwrap_err();
+  assert_eq!(av.as_slice(), &["a", "b"][..]);
+
+  let av: ArrayVec<[i32; 0]> = ArrayVec::new();
+  assert_eq!(av.into_array(), Ok([]));
+}
+
+#[test]
+fn ArrayVec_into_inner() {
+  let mut av: ArrayVec<[i32; 5]> = ArrayVec::new();
+  av.push(7);
+  av.push(8);
+  assert_eq!(av.into_inner(), [7, 8, 0, 0, 0]);
+
+  // slots vacated without dropping anything are defaulted too
+  let mut av = array_vec!([i32; 4], 1, 2, 3, 4);
+  av.truncate(1);
+  assert_eq!(av.into_inner(), [1, 0, 0, 0]);
+
+  let av = array_vec!([String; 3], String::from("a"));
+  assert_eq!(av.into_inner(), [String::from("a"), String::new(), String::new()]);
+}
+
+#[test]
+fn ArrayVec_try_insert() {
+  let mut av = array_vec!([i32; 4], 1, 2);
+  assert_eq!(av.try_insert(0, 0), Ok(()));
+  assert_eq!(av.try_insert(av.len(), 3), Ok(()));
+  assert_eq!(av.as_slice(), &[0, 1, 2, 3][..]);
+  assert_eq!(av.try_insert(2, 9), Err(9));
+  assert_eq!(av.try_insert(4, 9), Err(9));
+  assert_eq!(av.as_slice(), &[0, 1, 2, 3][..]);
+}
+
+#[test]
+fn ArrayVec_try_insert_full_hands_back_the_item() {
+  let mut av = array_vec!([String; 2], String::from("a"), String::from("b"));
+  let item = String::from("c");
+  let ptr = item.as_ptr();
+  let back = av.try_insert(1, item).unwrap_err();
+  assert_eq!(back, "c");
+  assert_eq!(back.as_ptr(), ptr);
+  assert_eq!(av.as_slice(), &["a", "b"][..]);
+
+  let mut av = array_vec!([Droppy; 2], Droppy(1), Droppy(2));
+  let before = drops();
+  let back = av.try_insert(0, Droppy(3)).unwrap_err();
+  assert_eq!(drops(), before);
+  assert_eq!(back, Droppy(3));
+  assert!(av.iter().map(|d| d.0).eq(vec![1, 2]));
+}
+
+#[test]
+#[should_panic]
+fn ArrayVec_try_insert_out_of_bounds() {
+  let mut av = array_vec!([i32; 4], 1);
+  let _ = av.try_insert(2, 5);
+}
+
+#[test]
+fn ArrayVec_partition() {
+  let av: ArrayVec<[i32; 8]> = (1..=8).collect();
+  let (small, big) = av.partition(|x| *x < 3 || *x == 6);
+  assert_eq!(small.as_slice(), &[1, 2, 6][..]);
+  assert_eq!(big.as_slice(), &[3, 4, 5, 7, 8][..]);
+
+  let (all, none) = small.partition(|_| true);
+  assert_eq!(all.as_slice(), &[1, 2, 6][..]);
+  assert!(none.is_empty());
+  let (none, all) = big.partition(|_| false);
+  assert!(none.is_empty());
+  assert_eq!(all.as_slice(), &[3, 4, 5, 7, 8][..]);
+}
+
+#[test]
+fn ArrayVec_partition_moves() {
+  let names = ["ready a", "blocked b", "ready c"];
+  let tasks: ArrayVec<[String; 4]> =
+    names.iter().map(|s| s.to_string()).collect();
+  let (ready, blocked) = tasks.partition(|t| t.starts_with("ready"));
+  assert_eq!(ready.as_slice(), &["ready a", "ready c"][..]);
+  assert_eq!(blocked.as_slice(), &["blocked b"][..]);
+
+  let av: ArrayVec<[Droppy; 4]> = (1..=4).map(Droppy).collect();
+  let before = drops();
+  let (a, b) = av.partition(|d| d.0 % 2 == 0);
+  assert_eq!(drops(), before);
+  drop((a, b));
+  assert_eq!(drops(), before + 4);
+}
+
+#[test]
+fn ArrayVec_pop_if() {
+  let mut av: ArrayVec<[i32; 4]> = ArrayVec::new();
+  assert_eq!(av.pop_if(|_| unreachable!()), None);
+
+  av.extend_from_slice(&[1, 2, 3]);
+  let mut calls = 0;
+  assert_eq!(
+    av.pop_if(|x| {
+      calls += 1;
+      *x == 1
+    }),
+    None
+  );
+  assert_eq!(calls, 1);
+  assert_eq!(av.as_slice(), &[1, 2, 3][..]);
+
+  // the predicate may change the element it gets
+  assert_eq!(
+    av.pop_if(|x| {
+      calls += 1;
+      *x *= 10;
+      false
+    }),
+    None
+  );
+  assert_eq!(av.as_slice(), &[1, 2, 30][..]);
+  assert_eq!(av.pop_if(|x| *x == 30), Some(30));
+  assert_eq!(calls, 2);
+  assert_eq!(av.as_slice(), &[1, 2][..]);
+}
+
+#[test]
+fn ArrayVec_push_and_get() {
+  let mut av: ArrayVec<[String; 3]> = ArrayVec::new();
+  av.push_and_get(String::from("a")).push('!');
+  let b = av.push_and_get(String::from("b"));
+  b.push('?');
+  assert_eq!(av[1], "b?");
+  assert_eq!(av.as_slice(), &["a!", "b?"][..]);
+
+  let c = av.try_push_and_get(String::from("c")).unwrap();
+  *c = String::from("C");
+  assert_eq!(av[2], "C");
+  assert_eq!(av.try_push_and_get(String::from("d")), Err(String::from("d")));
+  assert_eq!(av.len(), 3);
+}
+
+#[test]
+#[should_panic]
+fn ArrayVec_push_and_get_full() {
+  let mut av = array_vec!([i32; 1], 1);
+  av.push_and_get(2);
+}
+
+#[test]
+fn ArrayVec_insert_from_slice() {
+  let mut av = array_vec!([i32; 10], 1, 2, 3);
+  av.insert_from_slice(0, &[4, 5]);
+  assert_eq!(av.as_slice(), &[4, 5, 1, 2, 3][..]);
+  av.insert_from_slice(av.len(), &[6]);
+  assert_eq!(av.as_slice(), &[4, 5, 1, 2, 3, 6][..]);
+  av.insert_from_slice(3, &[7, 8, 9]);
+  assert_eq!(av.as_slice(), &[4, 5, 1, 7, 8, 9, 2, 3, 6][..]);
+  av.insert_from_slice(2, &[]);
+  assert_eq!(av.as_slice(), &[4, 5, 1, 7, 8, 9, 2, 3, 6][..]);
+
+  let mut v = vec![1, 2, 3];
+  let mut av: ArrayVec<[i32; 10]> = v.iter().copied().collect();
+  v.splice(1..1, [7, 8].iter().copied());
+  av.insert_from_slice(1, &[7, 8]);
+  assert_eq!(av.as_slice(), v.as_slice());
+}
+
+#[test]
+fn ArrayVec_try_insert_from_slice() {
+  let mut av: ArrayVec<[Tracked; 4]> = array_vec!([Tracked; 4], Tracked(1));
+  let before = clones();
+  let extra = [Tracked(2), Tracked(3), Tracked(4), Tracked(5)];
+  assert_eq!(
+    av.try_insert_from_slice(0, &extra),
+    Err(CapacityError::new((), 5, 4))
+  );
+  assert_eq!(clones(), before);
+  assert_eq!(av.try_insert_from_slice(1, &extra[..3]), Ok(()));
+  assert_eq!(clones(), before + 3);
+  assert!(av.iter().map(|t| t.0).eq(1..=4));
+}
+
+#[test]
+fn ArrayVec_insert_from_slice_overflow() {
+  let mut av = array_vec!([i32; 4], 1, 2);
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    av.insert_from_slice(1, &[7, 8, 9]);
+  }));
+  assert!(result.is_err());
+  assert_eq!(av.as_slice(), &[1, 2][..]);
+}
+
+#[test]
+#[should_panic]
+fn ArrayVec_insert_from_slice_out_of_bounds() {
+  let mut av = array_vec!([i32; 4], 1, 2);
+  av.insert_from_slice(3, &[7]);
+}
+
+#[test]
+fn ArrayVec_remove_item_and_if() {
+  let mut av = array_vec!([i32; 8], 1, 2, 3, 2, 4);
+  assert_eq!(av.remove_item(&2), Some(2));
+  assert_eq!(av.as_slice(), &[1, 3, 2, 4][..]);
+  assert_eq!(av.remove_item(&7), None);
+  assert_eq!(av.remove_if(|x| *x > 1), Some(3));
+  assert_eq!(av.as_slice(), &[1, 2, 4][..]);
+  assert_eq!(av.remove_if(|x| *x > 10), None);
+  assert_eq!(av.as_slice(), &[1, 2, 4][..]);
+}
+
+#[test]
+fn ArrayVec_swap_remove_item_and_if() {
+  let mut av = array_vec!([i32; 8], 1, 2, 3, 2, 4);
+  assert_eq!(av.swap_remove_item(&2), Some(2));
+  // the last element fills the hole
+  assert_eq!(av.as_slice(), &[1, 4, 3, 2][..]);
+  assert_eq!(av.swap_remove_item(&7), None);
+  assert_eq!(av.swap_remove_if(|x| *x == 2), Some(2));
+  assert_eq!(av.as_slice(), &[1, 4, 3][..]);
+  assert_eq!(av.swap_remove_if(|x| *x == 1), Some(1));
+  assert_eq!(av.as_slice(), &[3, 4][..]);
+
+  let mut av: ArrayVec<[i32; 2]> = ArrayVec::new();
+  assert_eq!(av.swap_remove_if(|_| true), None);
+}
+
+#[test]
+fn ArrayVec_insert_sorted() {
+  let mut av: ArrayVec<[i32; 8]> = ArrayVec::new();
+  for &x in [5, 1, 9, 5, 0, 9, 3].iter() {
+    av.insert_sorted(x);
+  }
+  assert_eq!(av.as_slice(), &[0, 1, 3, 5, 5, 9, 9][..]);
+  assert_eq!(av.insert_sorted(10), 7);
+  assert_eq!(av.try_insert_sorted(-1), Err(-1));
+}
+
+#[test]
+fn ArrayVec_insert_sorted_is_stable() {
+  // sorted by the first field only, the second one records insertion order
+  let mut av: ArrayVec<[(u8, u8); 8]> = ArrayVec::new();
+  assert_eq!(av.insert_sorted_by_key((2, 0), |p| p.0), 0);
+  assert_eq!(av.insert_sorted_by_key((1, 1), |p| p.0), 0);
+  assert_eq!(av.insert_sorted_by_key((2, 2), |p| p.0), 2);
+  assert_eq!(av.insert_sorted_by_key((1, 3), |p| p.0), 1);
+  assert_eq!(av.insert_sorted_by_key((2, 4), |p| p.0), 4);
+  assert_eq!(av.as_slice(), &[(1, 1), (1, 3), (2, 0), (2, 2), (2, 4)][..]);
+
+  // a reversed comparator keeps a descending vec
+  let mut av = array_vec!([i32; 8], 9, 5, 1);
+  assert_eq!(av.insert_sorted_by(6, |a, b| b.cmp(a)), 1);
+  assert_eq!(av.as_slice(), &[9, 6, 5, 1][..]);
+}
+
+#[test]
+#[should_panic]
+fn ArrayVec_insert_sorted_full() {
+  let mut av = array_vec!([i32; 2], 1, 2);
+  av.insert_sorted(0);
+}
+
+#[test]
+fn ArrayVec_remove_ends() {
+  let mut av = array_vec!([i32; 8], 1, 2, 3, 4);
+  assert_eq!(av.remove(0), 1);
+  assert_eq!(av.as_slice(), &[2, 3, 4][..]);
+  assert_eq!(av.remove(av.len() - 1), 4);
+  assert_eq!(av.as_slice(), &[2, 3][..]);
+  av.set_len(4);
+  assert_eq!(av.as_slice(), &[2, 3, 0, 0][..], "vacated slots are defaults");
+}
+
+#[test]
+fn ArrayVec_remove_drops() {
+  let mut av: ArrayVec<[Droppy; 8]> = (1..=5).map(Droppy).collect();
+  let before = drops();
+  let removed = av.remove(1);
+  assert_eq!(drops(), before, "nothing dropped while shifting");
+  drop(removed);
+  assert_eq!(drops(), before + 1);
+  assert!(av.iter().map(|d| d.0).eq(vec![1, 3, 4, 5]));
+}
+
+#[test]
+#[should_panic(
+  expected = "ArrayVec::remove> index 3 is out of bounds (len 3, capacity 4)"
+)]
+fn ArrayVec_remove_out_of_bounds() {
+  let mut av = array_vec!([i32; 4], 1, 2, 3);
+  av.remove(3);
+}
+
+#[test]
+fn ArrayVec_remove_matches_vec() {
+  let mut state = 0x8765_4321;
+  let mut av: ArrayVec<[u32; 256]> = (0..256).collect();
+  let mut v: Vec<u32> = (0..256).collect();
+  while !v.is_empty() {
+    let index = xorshift(&mut state) as usize % v.len();
+    assert_eq!(av.remove(index), v.remove(index));
+    assert_eq!(av.as_slice(), v.as_slice());
+  }
+}
+
+#[test]
+fn ArrayVec_resize_clones_one_less() {
+  for k in 0..5 {
+    let mut av: ArrayVec<[Tracked; 8]> = array_vec!([Tracked; 8], Tracked(1));
+    let before = clones();
+    av.resize(1 + k, Tracked(2));
+    assert_eq!(clones() - before, k.saturating_sub(1), "growing by {}", k);
+    assert_eq!(av.len(), 1 + k);
+    assert!(av[1..].iter().all(|t| *t == Tracked(2)));
+  }
+
+  let mut av: ArrayVec<[Tracked; 8]> = (0..6).map(Tracked).collect();
+  let before = clones();
+  av.resize(2, Tracked(9));
+  assert_eq!(clones(), before);
+  assert_eq!(av.as_slice(), &[Tracked(0), Tracked(1)][..]);
+}
+
+#[test]
+fn ArrayVec_extend_checks_size_hint_first() {
+  use std::panic::{catch_unwind, AssertUnwindSafe};
+  let mut av = array_vec!([i32; 4], 1, 2);
+
+  let mut iter = 10..13;
+  let result = catch_unwind(AssertUnwindSafe(|| av.extend(&mut iter)));
+  assert!(result.is_err());
+  assert_eq!(av.as_slice(), &[1, 2][..], "vec untouched");
+  assert_eq!(iter, 10..13, "iterator untouched");
+
+  let result = catch_unwind(AssertUnwindSafe(|| av.extend_from_slice(&[3; 3])));
+  assert!(result.is_err());
+  assert_eq!(av.as_slice(), &[1, 2][..]);
+
+  let result = catch_unwind(|| (0..5).collect::<ArrayVec<[i32; 4]>>());
+  assert!(result.is_err());
+
+  av.extend(3..5);
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4][..]);
+}
+
+#[test]
+fn ArrayVec_extend_unknown_size_hint() {
+  use std::panic::{catch_unwind, AssertUnwindSafe};
+  // `filter` gives a lower bound of 0, so we only find out when pushing
+  let mut av = array_vec!([i32; 4], 1, 2);
+  let result = catch_unwind(AssertUnwindSafe(|| {
+    av.extend((3..10).filter(|_| true));
+  }));
+  assert!(result.is_err());
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4][..]);
+
+  /// Claims to be empty, but isn't.
+  struct Liar(u32);
+  impl Iterator for Liar {
+    type Item = i32;
+    fn next(&mut self) -> Option<i32> {
+      self.0 = self.0.checked_sub(1)?;
+      Some(self.0 as i32)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+      (0, Some(0))
+    }
+  }
+  let mut av: ArrayVec<[i32; 4]> = Liar(3).collect();
+  assert_eq!(av.as_slice(), &[2, 1, 0][..]);
+  let result = catch_unwind(AssertUnwindSafe(|| av.extend(Liar(2))));
+  assert!(result.is_err());
+  assert_eq!(av.as_slice(), &[2, 1, 0, 1][..]);
+}
+
+#[test]
+fn ArrayVec_retain_panic_safety() {
+  use std::panic::{catch_unwind, AssertUnwindSafe};
+  for panic_at in 1..=10 {
+    let mut av: ArrayVec<[Droppy; 16]> = (1..=10).map(Droppy).collect();
+    let before = drops();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+      av.retain(|d| {
+        if d.0 == panic_at {
+          panic!("retain panic at {}", panic_at);
+        }
+        d.0 % 2 == 0
+      })
+    }));
+    assert!(result.is_err());
+
+    let expected: Vec<u32> =
+      (1..panic_at).filter(|x| x % 2 == 0).chain(panic_at..=10).collect();
+    assert_eq!(av.iter().map(|d| d.0).collect::<Vec<_>>(), expected);
+    let rejected = (1..panic_at).filter(|x| x % 2 != 0).count();
+    assert_eq!(drops() - before, rejected, "panic at {}", panic_at);
+
+    // the spare slots are all defaults, so nothing is dropped twice
+    let len = av.len();
+    av.set_len(16);
+    assert!(av[len..].iter().all(|d| d.0 == 0));
+    av.set_len(len);
+    drop(av);
+    assert_eq!(drops() - before, rejected + expected.len());
+  }
+}
+
+#[test]
+fn ArrayVecIterator_fold_matches_next() {
+  let av: ArrayVec<[u64; 1024]> = (0..1000).collect();
+  // order sensitive, so that the fold has to visit things in the same order
+  let step = |acc: u64, x: u64| acc.wrapping_mul(31) ^ x;
+  let mut by_next = 0;
+  for x in av.into_iter() {
+    by_next = step(by_next, x);
+  }
+  assert_eq!(av.into_iter().fold(0, step), by_next);
+  assert_eq!(av.into_iter().sum::<u64>(), (0..1000).sum());
+
+  let mut iter = av.into_iter();
+  iter.next();
+  let mut seen = Vec::new();
+  iter.for_each(|x| seen.push(x));
+  assert!(seen.into_iter().eq(1..1000));
+}
+
+#[test]
+#[allow(clippy::iter_nth_zero)]
+fn ArrayVecIterator_nth_last_skip() {
+  let av = array_vec!([i32; 8], 1, 2, 3, 4, 5);
+  let mut iter = av.into_iter();
+  assert_eq!(iter.nth(0), Some(1));
+  assert_eq!(iter.nth(1), Some(3));
+  assert_eq!(iter.size_hint(), (2, Some(2)));
+  assert_eq!(iter.nth(5), None);
+  assert_eq!(iter.next(), None);
+
+  assert_eq!(av.into_iter().last(), Some(5));
+  assert_eq!(ArrayVec::<[i32; 4]>::new().into_iter().last(), None);
+  let mut iter = av.into_iter();
+  iter.nth(4);
+  assert_eq!(iter.last(), None);
+
+  assert!(av.into_iter().skip(2).eq(3..=5));
+  assert!(av.into_iter().skip(9).eq(None));
+  assert_eq!(av.into_iter().step_by(2).collect::<Vec<_>>(), vec![1, 3, 5]);
+}
+
+#[test]
+fn ArrayVecIterator_drops() {
+  let make = || (1..=6).map(Droppy).collect::<ArrayVec<[Droppy; 8]>>();
+
+  let before = drops();
+  let mut iter = make().into_iter();
+  let third = iter.nth(2).unwrap();
+  assert_eq!(third.0, 3);
+  assert_eq!(drops(), before + 2, "skipped elements are dropped");
+  drop(iter);
+  assert_eq!(drops(), before + 5, "the rest drop with the iterator");
+  drop(third);
+
+  let before = drops();
+  let ids = make().into_iter().fold(Vec::new(), |mut ids, d| {
+    ids.push(d.0);
+    ids
+  });
+  assert_eq!(ids, vec![1, 2, 3, 4, 5, 6]);
+  assert_eq!(drops(), before + 6, "each element is dropped exactly once");
+}
@@ -0,0 +1,200 @@
+#![allow(bad_style)]
+
+use core::mem::size_of;
+use tinyvec::*;
+
+#[test]
+fn SmallArrayVec_sizes() {
+  assert_eq!(size_of::<SmallArrayVec<[u8; 8], u8>>(), 9);
+  assert_eq!(size_of::<SmallArrayVec<[u8; 0], u8>>(), 1);
+  assert_eq!(size_of::<SmallArrayVec<[u8; 512], u16>>(), 514);
+  assert_eq!(size_of::<SmallArrayVec<[u16; 4], u16>>(), 10);
+  assert_eq!(size_of::<SmallArrayVec<[u32; 4], u8>>(), 20);
+  assert_eq!(size_of::<SmallArrayVec<[u32; 4], u32>>(), 20);
+  assert_eq!(
+    size_of::<SmallArrayVec<[u8; 8], usize>>(),
+    size_of::<ArrayVec<[u8; 8]>>()
+  );
+}
+
+#[test]
+fn SmallArrayVec_push_pop() {
+  let mut av: SmallArrayVec<[i32; 4], u8> = Default::default();
+  assert_eq!(av.len(), 0);
+  assert_eq!(av.pop(), None);
+
+  av.push(10_i32);
+  assert_eq!(av.len(), 1);
+  assert_eq!(av[0], 10);
+  assert_eq!(av.pop(), Some(10));
+  assert_eq!(av.len(), 0);
+  assert_eq!(av.pop(), None);
+
+  av.push(10);
+  av.push(11);
+  av.push(12);
+  av.push(13);
+  assert_eq!(av.as_slice(), &[10, 11, 12, 13][..]);
+  assert_eq!(av.len(), 4);
+  assert_eq!(av.pop(), Some(13));
+  assert_eq!(av.pop(), Some(12));
+  assert_eq!(av.pop(), Some(11));
+  assert_eq!(av.pop(), Some(10));
+  assert_eq!(av.len(), 0);
+  assert_eq!(av.pop(), None);
+}
+
+#[test]
+#[should_panic]
+fn SmallArrayVec_push_overflow() {
+  let mut av: SmallArrayVec<[i32; 0], u8> = Default::default();
+  av.push(7);
+}
+
+#[test]
+fn SmallArrayVec_capacity_limited_by_len_type() {
+  let mut av: SmallArrayVec<[u8; 512], u8> = SmallArrayVec::new();
+  assert_eq!(av.capacity(), 255);
+  for i in 0..255 {
+    av.push(i as u8);
+  }
+  assert_eq!(av.len(), 255);
+  assert_eq!(av[254], 254);
+
+  let result = std::panic::catch_unwind(move || {
+    let mut av = av;
+    av.push(0);
+  });
+  assert!(result.is_err());
+
+  let av: SmallArrayVec<[u8; 256], u8> = SmallArrayVec::new();
+  assert_eq!(av.capacity(), 255);
+  let av: SmallArrayVec<[u8; 256], u16> = SmallArrayVec::new();
+  assert_eq!(av.capacity(), 256);
+
+  assert!(SmallArrayVec::<[u8; 512], u8>::try_from_array_len([0; 512], 256)
+    .is_err());
+  assert!(SmallArrayVec::<[u8; 512], u8>::try_from_array_len([0; 512], 255)
+    .is_ok());
+  assert!(SmallArrayVec::<[u8; 4], u8>::try_from_array_len([0; 4], 5).is_err());
+}
+
+#[test]
+fn SmallArrayVec_resize_to_boundary() {
+  let mut av: SmallArrayVec<[u8; 512], u8> = SmallArrayVec::new();
+  av.resize(255, 7);
+  assert_eq!(av.len(), 255);
+  assert!(av.iter().all(|&x| x == 7));
+  av.truncate(1);
+  assert_eq!(av.as_slice(), &[7][..]);
+  av.set_len(255);
+  assert_eq!(av.len(), 255);
+  av.clear();
+  assert!(av.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn SmallArrayVec_set_len_past_len_type() {
+  let mut av: SmallArrayVec<[u8; 512], u8> = SmallArrayVec::new();
+  av.set_len(256);
+}
+
+#[test]
+fn SmallArrayVec_insert() {
+  let mut av: SmallArrayVec<[i32; 4], u8> = SmallArrayVec::new();
+  av.insert(0, 2);
+  av.insert(0, 1);
+  av.insert(2, 4);
+  av.insert(2, 3);
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4][..]);
+}
+
+#[test]
+#[should_panic]
+fn SmallArrayVec_insert_full() {
+  let mut av: SmallArrayVec<[i32; 2], u8> = SmallArrayVec::from([1, 2]);
+  av.insert(0, 0);
+}
+
+#[test]
+fn SmallArrayVec_remove() {
+  let mut av: SmallArrayVec<[i32; 10], u8> = SmallArrayVec::new();
+  av.push(1);
+  av.push(2);
+  av.push(3);
+  assert_eq!(av.remove(1), 2);
+  assert_eq!(av.as_slice(), &[1, 3][..]);
+  assert_eq!(av.swap_remove(0), 1);
+  assert_eq!(av.as_slice(), &[3][..]);
+  assert_eq!(av.swap_remove(0), 3);
+  assert!(av.is_empty());
+}
+
+#[test]
+fn SmallArrayVec_drain() {
+  let mut av: SmallArrayVec<[i32; 10], u16> = SmallArrayVec::new();
+  av.extend(1..=5);
+
+  let drained: Vec<i32> = av.drain(1..3).collect();
+  assert_eq!(drained, vec![2, 3]);
+  assert_eq!(av.as_slice(), &[1, 4, 5][..]);
+
+  // dropping the drain early still removes the whole range
+  av.drain(..2);
+  assert_eq!(av.as_slice(), &[5][..]);
+
+  av.drain(..);
+  assert!(av.is_empty());
+}
+
+#[test]
+fn SmallArrayVec_drain_partial_and_forget() {
+  let mut av: SmallArrayVec<[i32; 8], u8> = (1..=6).collect();
+  {
+    let mut d = av.drain(1..5);
+    assert_eq!(d.len(), 4);
+    assert_eq!(d.next(), Some(2));
+    assert_eq!(d.next_back(), Some(5));
+    assert_eq!(d.len(), 2);
+  }
+  assert_eq!(av.as_slice(), &[1, 6][..]);
+
+  // forgetting the drain leaks the tail but leaves a valid vec behind
+  let mut av: SmallArrayVec<[i32; 8], u8> = (1..=6).collect();
+  core::mem::forget(av.drain(2..4));
+  assert_eq!(av.as_slice(), &[1, 2][..]);
+}
+
+#[test]
+fn SmallArrayVec_drain_large_range() {
+  let mut av: SmallArrayVec<[u32; 1024], u16> = (0..1024).collect();
+  let drained: Vec<u32> = av.drain(10..1000).collect();
+  assert!(drained.into_iter().eq(10..1000));
+  assert!(av.iter().cloned().eq((0..10).chain(1000..1024)));
+}
+
+#[test]
+fn SmallArrayVec_split_off_and_append() {
+  let mut av: SmallArrayVec<[i32; 8], u8> = (1..=6).collect();
+  let mut av2 = av.split_off(4);
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4][..]);
+  assert_eq!(av2.as_slice(), &[5, 6][..]);
+  av.append(&mut av2);
+  assert_eq!(av.as_slice(), &[1, 2, 3, 4, 5, 6][..]);
+  assert!(av2.is_empty());
+  av.retain(|&x| x % 2 == 0);
+  assert_eq!(av.as_slice(), &[2, 4, 6][..]);
+}
+
+#[test]
+fn SmallArrayVec_conversions() {
+  let av: SmallArrayVec<[i32; 4], u8> = (1..=3).collect();
+  assert_eq!(format!("{:?}", av), "[1, 2, 3]");
+
+  let full: ArrayVec<[i32; 4]> = av.into();
+  assert_eq!(full.as_slice(), &[1, 2, 3][..]);
+
+  let collected: Vec<i32> = av.into_iter().collect();
+  assert_eq!(collected, vec![1, 2, 3]);
+}
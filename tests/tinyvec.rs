@@ -1,8 +1,40 @@
 #![allow(bad_style)]
 #![allow(clippy::redundant_clone)]
+#![allow(clippy::missing_const_for_thread_local)]
 
 use tinyvec::*;
-use std::iter::FromIterator;
+use std::{cell::Cell, iter::FromIterator};
+
+thread_local! {
+  static CLONES: Cell<usize> = Cell::new(0);
+  static DROPS: Cell<usize> = Cell::new(0);
+}
+
+/// Counts its clones, and its drops when the id isn't zero.
+#[derive(Debug, Default, PartialEq)]
+struct Tracked(u32);
+impl Clone for Tracked {
+  fn clone(&self) -> Self {
+    CLONES.with(|c| c.set(c.get() + 1));
+    Tracked(self.0)
+  }
+}
+impl Drop for Tracked {
+  fn drop(&mut self) {
+    if self.0 != 0 {
+      DROPS.with(|d| d.set(d.get() + 1));
+    }
+  }
+}
+fn clones() -> usize {
+  CLONES.with(Cell::get)
+}
+fn drops() -> usize {
+  DROPS.with(Cell::get)
+}
+fn ids<A: Array<Item = Tracked>>(tv: &TinyVec<A>) -> Vec<u32> {
+  tv.iter().map(|t| t.0).collect()
+}
 
 #[test]
 fn TinyVec_swap_remove() {
@@ -50,9 +82,1107 @@ fn TinyVec_drain() {
   assert_eq!(Vec::from_iter(tv.clone().drain(1..=2)), vec![2, 3]);
 }
 
+#[test]
+fn TinyVec_drain_matches_vec() {
+  fn check(tv: TinyVec<[i32; 4]>) {
+    let inline = tv.is_inline();
+    let len = tv.len() as i32;
+    let ranges = [(0, 0), (0, 2), (1, 3), (2, len), (0, len)];
+    for &(start, end) in ranges.iter() {
+      let (start, end) = (start as usize, end as usize);
+      // front only, back only, alternating, and dropped half way
+      for mode in 0..4 {
+        let mut tv = tv.clone();
+        let mut v = tv.to_vec();
+        let mut tvd = tv.drain(start..end);
+        let mut vd = v.drain(start..end);
+        assert_eq!(tvd.len(), vd.len());
+        let mut step = 0;
+        loop {
+          let (a, b) = match mode {
+            0 => (tvd.next(), vd.next()),
+            1 => (tvd.next_back(), vd.next_back()),
+            2 if step % 2 == 0 => (tvd.next(), vd.next()),
+            2 => (tvd.next_back(), vd.next_back()),
+            _ if step == 1 => break,
+            _ => (tvd.next(), vd.next()),
+          };
+          assert_eq!(a, b);
+          assert_eq!(tvd.len(), vd.len());
+          if a.is_none() {
+            break;
+          }
+          step += 1;
+        }
+        drop(tvd);
+        drop(vd);
+        assert_eq!(tv.as_slice(), v.as_slice());
+        assert_eq!(tv.is_inline(), inline);
+      }
+    }
+  }
+
+  check(tiny_vec!([i32; 4], 1, 2, 3, 4));
+  check(TinyVec::Heap(vec![1, 2, 3, 4, 5, 6, 7]));
+}
+
+#[test]
+fn TinyVec_retain_keeps_variant() {
+  let mut tv: TinyVec<[i32; 4]> = tiny_vec!([i32; 4], 1, 2, 3, 4);
+  tv.retain(|&x| x % 2 == 0);
+  assert_eq!(tv.as_slice(), &[2, 4][..]);
+  assert!(tv.is_inline());
+
+  // a heap vec that shrinks under the inline capacity stays on the heap
+  let mut tv: TinyVec<[i32; 4]> = (1..=10).collect();
+  assert!(tv.is_heap());
+  tv.retain(|&x| x % 4 == 0);
+  assert_eq!(tv.as_slice(), &[4, 8][..]);
+  assert!(tv.is_heap());
+}
+
+#[test]
+fn TinyVec_retain_mut() {
+  for &n in [4, 10].iter() {
+    let mut tv: TinyVec<[i32; 4]> = (1..=n).collect();
+    let inline = tv.is_inline();
+    let mut v: Vec<i32> = (1..=n).collect();
+    let mut step = |x: &mut i32| {
+      *x *= 3;
+      *x % 2 == 0
+    };
+    tv.retain_mut(&mut step);
+    v.iter_mut().for_each(|x| *x *= 3);
+    v.retain(|x| x % 2 == 0);
+    assert_eq!(tv.as_slice(), v.as_slice());
+    assert_eq!(tv.is_inline(), inline);
+  }
+}
+
+#[test]
+fn TinyVec_retain_mut_heap_panic() {
+  use std::panic::{catch_unwind, AssertUnwindSafe};
+  let mut tv: TinyVec<[i32; 2]> = (1..=8).collect();
+  let result = catch_unwind(AssertUnwindSafe(|| {
+    tv.retain_mut(|x| {
+      assert!(*x != 5);
+      *x % 2 == 0
+    })
+  }));
+  assert!(result.is_err());
+  assert_eq!(tv.as_slice(), &[2, 4, 5, 6, 7, 8][..]);
+}
+
+
+#[test]
+fn TinyVec_insert_spills_when_full() {
+  for index in 0..=4 {
+    let mut tv = tiny_vec!([i32; 4], 1, 2, 3, 4);
+    let mut v = vec![1, 2, 3, 4];
+    tv.insert(index, 9);
+    v.insert(index, 9);
+    assert_eq!(tv.as_slice(), v.as_slice());
+    assert!(tv.is_heap());
+  }
+
+  let mut tv = tiny_vec!([i32; 4], 1, 2, 3);
+  tv.insert(3, 4);
+  assert_eq!(tv.as_slice(), &[1, 2, 3, 4][..]);
+  assert!(tv.is_inline());
+}
+
+#[test]
+fn TinyVec_insert_from_slice() {
+  for &len in [0, 2, 6].iter() {
+    for index in 0..=len {
+      for &n in [0, 1, 3].iter() {
+        let mut tv: TinyVec<[i32; 4]> = (0..len as i32).collect();
+        let mut v: Vec<i32> = (0..len as i32).collect();
+        let sli: Vec<i32> = (100..100 + n).collect();
+        tv.insert_from_slice(index, &sli);
+        v.splice(index..index, sli.iter().copied());
+        assert_eq!(tv.as_slice(), v.as_slice());
+        assert_eq!(tv.is_heap(), len > 4 || v.len() > 4);
+      }
+    }
+  }
+
+  // this insert is the one that spills, with one exact allocation
+  let mut tv = tiny_vec!([i32; 4], 1, 2, 3);
+  tv.insert_from_slice(1, &[7, 8]);
+  assert_eq!(tv.as_slice(), &[1, 7, 8, 2, 3][..]);
+  assert!(tv.is_heap());
+  assert_eq!(tv.capacity(), 5);
+}
+
+#[test]
+#[should_panic]
+fn TinyVec_insert_from_slice_out_of_bounds() {
+  let mut tv = tiny_vec!([i32; 4], 1, 2);
+  tv.insert_from_slice(3, &[5]);
+}
+
+#[test]
+fn TinyVec_remove_stays_on_heap() {
+  let mut tv: TinyVec<[i32; 4]> = (1..=5).collect();
+  assert_eq!(tv.remove(0), 1);
+  assert_eq!(tv.swap_remove(0), 2);
+  assert_eq!(tv.remove(2), 4);
+  assert_eq!(tv.as_slice(), &[5, 3][..]);
+  assert!(tv.is_heap());
+}
+
+#[test]
+#[should_panic]
+fn TinyVec_insert_full_inline_out_of_bounds() {
+  let mut tv = tiny_vec!([i32; 2], 1, 2);
+  tv.insert(3, 9);
+}
+
+#[test]
+#[should_panic]
+fn TinyVec_insert_heap_out_of_bounds() {
+  let mut tv: TinyVec<[i32; 2]> = (1..=3).collect();
+  tv.insert(4, 9);
+}
+
+#[test]
+#[should_panic]
+fn TinyVec_remove_out_of_bounds() {
+  let mut tv = tiny_vec!([i32; 4], 1, 2);
+  tv.remove(2);
+}
+
+#[test]
+#[should_panic]
+fn TinyVec_swap_remove_heap_out_of_bounds() {
+  let mut tv: TinyVec<[i32; 2]> = (1..=3).collect();
+  tv.swap_remove(3);
+}
+
+#[test]
+fn TinyVec_move_to_the_heap() {
+  let mut tv: TinyVec<[i32; 4]> = TinyVec::new();
+  tv.move_to_the_heap();
+  assert!(tv.is_heap());
+  assert!(tv.is_empty());
+
+  let mut tv: TinyVec<[Tracked; 4]> = (1..=4).map(Tracked).collect();
+  let (clones_before, drops_before) = (clones(), drops());
+  tv.move_to_the_heap();
+  assert!(tv.is_heap());
+  assert_eq!(ids(&tv), vec![1, 2, 3, 4]);
+  assert_eq!((clones(), drops()), (clones_before, drops_before));
+
+  // already on the heap: nothing happens
+  let cap = tv.capacity();
+  tv.move_to_the_heap();
+  assert_eq!(tv.capacity(), cap);
+  assert_eq!(ids(&tv), vec![1, 2, 3, 4]);
+
+  // even a full heap vec isn't grown
+  let mut tv: TinyVec<[i32; 4]> = TinyVec::from(vec![1, 2, 3]);
+  let (ptr, cap) = (tv.as_ptr(), tv.capacity());
+  assert_eq!(tv.len(), cap);
+  tv.move_to_the_heap();
+  assert_eq!((tv.as_ptr(), tv.capacity()), (ptr, cap));
+}
+
+#[test]
+fn TinyVec_move_to_the_heap_and_reserve() {
+  let mut tv = tiny_vec!([i32; 4], 1, 2, 3);
+  tv.move_to_the_heap_and_reserve(10);
+  assert_eq!(tv.as_slice(), &[1, 2, 3][..]);
+  assert_eq!(tv.capacity(), 13);
+
+  // the whole reservation was made up front, so filling it doesn't grow it
+  for i in 4..=13 {
+    tv.push(i);
+  }
+  assert_eq!(tv.capacity(), 13);
+
+  tv.move_to_the_heap_and_reserve(7);
+  assert!(tv.capacity() >= 20);
+}
+
+#[test]
+fn TinyVec_shrink_to_fit() {
+  let mut tv: TinyVec<[Tracked; 8]> = (1..=40).map(Tracked).collect();
+  tv.truncate(12);
+  assert!(tv.capacity() >= 40);
+  tv.shrink_to_fit();
+  assert!(tv.is_heap());
+  assert_eq!(tv.capacity(), 12);
+
+  tv.truncate(3);
+  let (clones_before, drops_before) = (clones(), drops());
+  tv.shrink_to_fit();
+  assert!(tv.is_inline());
+  assert_eq!(ids(&tv), vec![1, 2, 3]);
+  assert_eq!((clones(), drops()), (clones_before, drops_before));
+
+  // and inline is left alone
+  tv.shrink_to_fit();
+  assert!(tv.is_inline());
+  assert_eq!(ids(&tv), vec![1, 2, 3]);
+}
+
+#[test]
+fn TinyVec_try_move_to_inline() {
+  // already inline
+  let mut tv: TinyVec<[Tracked; 4]> = (1..=3).map(Tracked).collect();
+  assert!(tv.try_move_to_inline());
+  assert_eq!(ids(&tv), vec![1, 2, 3]);
+
+  // too long: stays on the heap, allocation untouched
+  let mut tv: TinyVec<[Tracked; 4]> = (1..=20).map(Tracked).collect();
+  tv.truncate(5);
+  let (ptr, cap) = (tv.as_ptr(), tv.capacity());
+  assert!(!tv.try_move_to_inline());
+  assert!(tv.is_heap());
+  assert_eq!((tv.as_ptr(), tv.capacity()), (ptr, cap));
+
+  // fits: moved, not cloned or dropped
+  tv.truncate(4);
+  let (clones_before, drops_before) = (clones(), drops());
+  assert!(tv.try_move_to_inline());
+  assert!(tv.is_inline());
+  assert_eq!(ids(&tv), vec![1, 2, 3, 4]);
+  assert_eq!((clones(), drops()), (clones_before, drops_before));
+}
+
+#[test]
+fn TinyVec_splice_matches_vec() {
+  let ranges = [(0, 0), (0, 2), (1, 3), (2, 4), (0, 4), (4, 4)];
+  for &start_len in [4, 9].iter() {
+    for &(start, end) in ranges.iter() {
+      for &count in [0, 1, 2, 5, 12].iter() {
+        // take nothing, take one, or take everything from the splice
+        for &taken in [0, 1, usize::MAX].iter() {
+          let mut tv: TinyVec<[i32; 8]> = (0..start_len).collect();
+          let mut v: Vec<i32> = (0..start_len).collect();
+          let new = 100..100 + count;
+          let a: Vec<i32> =
+            tv.splice(start..end, new.clone()).take(taken).collect();
+          let b: Vec<i32> =
+            v.splice(start..end, new).take(taken).collect();
+          assert_eq!(a, b);
+          assert_eq!(tv.as_slice(), v.as_slice());
+          assert_eq!(tv.is_heap(), start_len > 8 || v.len() > 8);
+        }
+      }
+    }
+  }
+}
+
+#[test]
+fn TinyVec_splice_iterator() {
+  let mut tv: TinyVec<[Tracked; 4]> = (1..=4).map(Tracked).collect();
+  let before = drops();
+  {
+    let mut splice = tv.splice(0..3, (5..=10).map(Tracked));
+    assert_eq!(splice.len(), 3);
+    assert_eq!(splice.next_back().map(|t| t.0), Some(3));
+    assert_eq!(splice.len(), 2);
+    assert_eq!(drops(), before + 1);
+  }
+  // 1 and 2 were dropped along with the splice
+  assert_eq!(drops(), before + 3);
+  assert_eq!(ids(&tv), vec![5, 6, 7, 8, 9, 10, 4]);
+  assert!(tv.is_heap());
+  // the size hint was used for the one spill
+  assert_eq!(tv.capacity(), 7);
+}
+
+#[test]
+#[should_panic]
+fn TinyVec_splice_out_of_bounds() {
+  let mut tv = tiny_vec!([i32; 4], 1, 2);
+  tv.splice(1..3, None);
+}
+
+#[test]
+fn TinyVec_split_off_variants() {
+  // inline source, inline tail (an inline tail can never need the heap)
+  let mut tv = tiny_vec!([i32; 4], 1, 2, 3, 4);
+  let tail = tv.split_off(1);
+  assert_eq!(tv.as_slice(), &[1][..]);
+  assert_eq!(tail.as_slice(), &[2, 3, 4][..]);
+  assert!(tv.is_inline() && tail.is_inline());
+
+  // heap source, tail that fits inline
+  let mut tv: TinyVec<[i32; 4]> = (1..=10).collect();
+  let tail = tv.split_off(6);
+  assert_eq!(tv.as_slice(), &[1, 2, 3, 4, 5, 6][..]);
+  assert_eq!(tail.as_slice(), &[7, 8, 9, 10][..]);
+  assert!(tv.is_heap() && tail.is_inline());
+
+  // heap source, tail that needs the heap
+  let mut tv: TinyVec<[i32; 4]> = (1..=10).collect();
+  let tail = tv.split_off(2);
+  assert_eq!(tv.as_slice(), &[1, 2][..]);
+  assert_eq!(tail.as_slice(), &[3, 4, 5, 6, 7, 8, 9, 10][..]);
+  assert!(tv.is_heap() && tail.is_heap());
+}
+
+#[test]
+fn TinyVec_split_off_edges() {
+  for &n in [3, 10].iter() {
+    let mut tv: TinyVec<[i32; 4]> = (1..=n).collect();
+    let inline = tv.is_inline();
+    let tail = tv.split_off(0);
+    assert!(tv.is_empty());
+    assert_eq!(tail.len(), n as usize);
+    assert_eq!(tv.is_inline(), inline);
+
+    let mut tv: TinyVec<[i32; 4]> = (1..=n).collect();
+    let tail = tv.split_off(n as usize);
+    assert_eq!(tv.len(), n as usize);
+    assert!(tail.is_empty());
+    assert!(tail.is_inline());
+  }
+}
+
+#[test]
+#[should_panic]
+fn TinyVec_split_off_heap_out_of_bounds() {
+  let mut tv: TinyVec<[i32; 2]> = (1..=3).collect();
+  tv.split_off(4);
+}
+
+#[test]
+fn TinyVec_truncate_and_clear_keep_variant_and_capacity() {
+  let mut tv = tiny_vec!([i32; 4], 1, 2, 3);
+  tv.truncate(1);
+  assert!(tv.is_inline());
+  assert_eq!(tv.capacity(), 4);
+  tv.clear();
+  assert!(tv.is_inline());
+  assert_eq!(tv.capacity(), 4);
+
+  let mut tv: TinyVec<[i32; 4]> = (1..=10).collect();
+  let (ptr, cap) = (tv.as_ptr(), tv.capacity());
+  tv.truncate(2);
+  assert!(tv.is_heap());
+  assert_eq!((tv.as_ptr(), tv.capacity()), (ptr, cap));
+  tv.truncate(20);
+  assert_eq!(tv.as_slice(), &[1, 2][..]);
+  tv.clear();
+  assert!(tv.is_heap());
+  assert_eq!((tv.as_ptr(), tv.capacity()), (ptr, cap));
+
+  // refilling a cleared heap vec reuses the allocation
+  for round in 0..3 {
+    tv.clear();
+    tv.extend(round..round + 10);
+    assert_eq!((tv.as_ptr(), tv.capacity()), (ptr, cap));
+  }
+}
+
+#[test]
+fn TinyVec_split_off_inline() {
+  // inline source, the tail always fits
+  let mut tv = tiny_vec!([i32; 4], 1, 2, 3, 4);
+  let tail = tv.split_off_inline(0).unwrap();
+  assert_eq!(tail.as_slice(), &[1, 2, 3, 4][..]);
+  assert!(tv.is_empty() && tv.is_inline());
+
+  // heap source, tail exactly fits
+  let mut tv: TinyVec<[i32; 4]> = (1..=10).collect();
+  let tail = tv.split_off_inline(6).unwrap();
+  assert_eq!(tv.as_slice(), &[1, 2, 3, 4, 5, 6][..]);
+  assert_eq!(tail.as_slice(), &[7, 8, 9, 10][..]);
+  assert!(tv.is_heap());
+
+  // heap source, tail doesn't fit
+  let mut tv: TinyVec<[i32; 4]> = (1..=10).collect();
+  let cap = tv.capacity();
+  assert_eq!(tv.split_off_inline(5), Err(5));
+  assert_eq!(tv.as_slice(), &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10][..]);
+  assert_eq!(tv.capacity(), cap);
+
+  let tail = tv.split_off_inline(10).unwrap();
+  assert!(tail.is_empty());
+  assert_eq!(tv.len(), 10);
+}
+
+#[test]
+#[should_panic]
+fn TinyVec_split_off_inline_out_of_bounds() {
+  let mut tv = tiny_vec!([i32; 4], 1, 2);
+  let _ = tv.split_off_inline(3);
+}
+
+#[test]
+fn TinyVec_append_moves_once() {
+  fn make(ids: std::ops::RangeInclusive<u32>) -> TinyVec<[Tracked; 4]> {
+    ids.map(Tracked).collect()
+  }
+  let mut small_heap = make(2..=6);
+  small_heap.truncate(3);
+  let cases = [
+    // inline + inline that fits, and that overflows
+    (make(1..=2), make(3..=4), true),
+    (make(1..=3), make(4..=5), false),
+    // heap + inline, inline + heap (that fits and that doesn't)
+    (make(1..=6), make(7..=8), false),
+    (make(1..=1), make(2..=6), false),
+    (make(1..=1), small_heap.clone(), true),
+  ];
+  for (mut tv, mut other, inline) in cases.iter().cloned() {
+    let (clones_before, drops_before) = (clones(), drops());
+    let mut expected = ids(&tv);
+    expected.extend(ids(&other));
+    let other_heap_cap = if other.is_heap() {
+      Some(other.capacity())
+    } else {
+      None
+    };
+
+    tv.append(&mut other);
+    assert_eq!(clones(), clones_before);
+    assert_eq!(drops(), drops_before);
+    assert_eq!(ids(&tv), expected);
+    assert_eq!(tv.is_inline(), inline);
+    assert!(other.is_empty());
+    if let Some(cap) = other_heap_cap {
+      assert_eq!(other.capacity(), cap);
+    }
+
+    drop(tv);
+    assert_eq!(drops(), drops_before + expected.len());
+  }
+}
+
+#[test]
+fn TinyVec_dedup_matches_vec() {
+  let inputs: [&[i32]; 6] = [
+    &[],
+    &[1],
+    &[1, 1, 1, 1],
+    &[1, 1, 2, 3, 3],
+    &[1, 2, 2, 3, 4, 4, 4, 5, 6, 6],
+    &[7, 7, 1, 2, 1, 1, 9, 9, 9, 9, 9, 3],
+  ];
+  for input in inputs.iter() {
+    let mut tv: TinyVec<[i32; 4]> = input.iter().copied().collect();
+    let inline = tv.is_inline();
+    let mut v = input.to_vec();
+    tv.dedup();
+    v.dedup();
+    assert_eq!(tv.as_slice(), v.as_slice());
+    assert_eq!(tv.is_inline(), inline);
+  }
+}
+
+#[test]
+fn TinyVec_dedup_drops_duplicates() {
+  for &n in [4, 12].iter() {
+    // ids 1..=n, grouped into runs of two by the `/ 2` key
+    let mut tv: TinyVec<[Tracked; 4]> = (1..=n).map(Tracked).collect();
+    let before = drops();
+    tv.dedup_by(|a, b| a.0 / 2 == b.0 / 2);
+    assert_eq!(drops(), before + n as usize / 2 - 1);
+    let expected: Vec<u32> = (0..=n / 2).map(|k| (k * 2).max(1)).collect();
+    assert_eq!(ids(&tv), expected);
+  }
+}
+
+#[test]
+fn TinyVec_dedup_by_key_mutating() {
+  let input = [1, -1, 2, -2, -2, 3, -4, 4];
+  for &n in [4, 8].iter() {
+    let mut tv: TinyVec<[i32; 4]> = input[..n].iter().copied().collect();
+    let inline = tv.is_inline();
+    let mut v = input[..n].to_vec();
+    let mut calls = 0;
+    tv.dedup_by_key(|x| {
+      calls += 1;
+      *x = x.abs();
+      *x
+    });
+    v.dedup_by_key(|x| {
+      *x = x.abs();
+      *x
+    });
+    assert!(calls >= n);
+    assert_eq!(tv.as_slice(), v.as_slice());
+    assert_eq!(tv.is_inline(), inline);
+  }
+}
+
+#[test]
+fn TinyVec_extend_from_slice_across_the_boundary() {
+  let mut tv = tiny_vec!([i32; 4], 1, 2);
+  tv.extend_from_slice(&[3, 4]);
+  assert_eq!(tv.as_slice(), &[1, 2, 3, 4][..]);
+  assert!(tv.is_inline());
+
+  tv.extend_from_slice(&[]);
+  assert!(tv.is_inline());
+
+  // spilling allocates exactly the final length, in one go
+  tv.extend_from_slice(&[5, 6, 7]);
+  assert_eq!(tv.as_slice(), &[1, 2, 3, 4, 5, 6, 7][..]);
+  assert_eq!(tv.capacity(), 7);
+
+  let mut tv: TinyVec<[Tracked; 4]> = (1..=3).map(Tracked).collect();
+  let more: Vec<Tracked> = (4..=9).map(Tracked).collect();
+  let before = clones();
+  tv.extend_from_slice(&more);
+  assert_eq!(clones(), before + 6);
+  assert_eq!(ids(&tv), (1..=9).collect::<Vec<_>>());
+  assert_eq!(tv.capacity(), 9);
+}
+
+#[test]
+fn TinyVec_extend_from_within() {
+  // LZ-style back-references that repeat the tail over and over
+  let mut tv: TinyVec<[u8; 8]> = tiny_vec!([u8; 8], 1, 2, 3);
+  tv.extend_from_within(1..);
+  assert_eq!(tv.as_slice(), &[1, 2, 3, 2, 3][..]);
+  tv.extend_from_within(3..5);
+  assert_eq!(tv.as_slice(), &[1, 2, 3, 2, 3, 2, 3][..]);
+  assert!(tv.is_inline());
+
+  // this one spills, and still copies the right elements
+  tv.extend_from_within(0..=3);
+  assert_eq!(tv.as_slice(), &[1, 2, 3, 2, 3, 2, 3, 1, 2, 3, 2][..]);
+  assert!(tv.is_heap());
+  tv.extend_from_within(9..);
+  assert_eq!(tv.as_slice(), &[1, 2, 3, 2, 3, 2, 3, 1, 2, 3, 2, 3, 2][..]);
+
+  let before = tv.clone();
+  tv.extend_from_within(4..4);
+  tv.extend_from_within(tv.len()..);
+  assert_eq!(tv, before);
+}
+
+#[test]
+#[should_panic]
+fn TinyVec_extend_from_within_out_of_bounds() {
+  let mut tv = tiny_vec!([u8; 4], 1, 2);
+  tv.extend_from_within(1..3);
+}
+
+#[cfg(feature = "rustc_1_57")]
+#[test]
+fn TinyVec_try_reserve() {
+  // fits inline, so nothing moves
+  let mut tv = tiny_vec!([i32; 4], 1, 2);
+  assert!(tv.try_reserve(2).is_ok());
+  assert!(tv.try_reserve_exact(2).is_ok());
+  assert!(tv.is_inline());
+
+  // doesn't fit, so it moves to the heap with room for everything
+  assert!(tv.try_reserve_exact(5).is_ok());
+  assert_eq!(tv.as_slice(), &[1, 2][..]);
+  assert_eq!(tv.capacity(), 7);
+  assert!(tv.try_reserve(20).is_ok());
+  assert!(tv.capacity() >= 22);
+
+  // errors from the heap are passed along, and nothing changes
+  let err: TryReserveError = tv.try_reserve(usize::MAX).unwrap_err();
+  let _ = format!("{:?}", err);
+  assert_eq!(tv.as_slice(), &[1, 2][..]);
+
+  let mut tv = tiny_vec!([i32; 4], 1, 2);
+  assert!(tv.try_reserve_exact(usize::MAX).is_err());
+  assert_eq!(tv.as_slice(), &[1, 2][..]);
+  assert!(tv.is_inline());
+}
+
+#[test]
+fn TinyVec_introspection() {
+  assert_eq!(TinyVec::<[u8; 6]>::inline_capacity(), 6);
+
+  let mut tv: TinyVec<[u8; 2]> = TinyVec::new();
+  assert!(tv.is_inline() && !tv.is_heap());
+  assert_eq!(tv.capacity(), 2);
+  tv.push(1);
+  tv.push(2);
+  assert!(tv.is_inline());
+  tv.push(3);
+  assert!(tv.is_heap() && !tv.is_inline());
+  assert!(tv.capacity() >= 3);
+  tv.truncate(1);
+  assert!(tv.is_heap());
+  tv.shrink_to_fit();
+  assert!(tv.is_inline());
+  assert_eq!(tv.capacity(), 2);
+}
+
+#[test]
+fn TinyVec_from_vec_keeps_the_allocation() {
+  let mut v = Vec::with_capacity(10);
+  v.extend_from_slice(&[1, 2, 3]);
+  let ptr = v.as_ptr();
+  let tv: TinyVec<[i32; 4]> = TinyVec::from_vec(v);
+  assert!(tv.is_heap());
+  assert_eq!(tv.as_ptr(), ptr);
+  assert_eq!(tv.capacity(), 10);
+  assert_eq!(tv.as_slice(), &[1, 2, 3][..]);
+
+  let v = vec![4, 5, 6, 7, 8, 9];
+  let ptr = v.as_ptr();
+  let tv: TinyVec<[i32; 4]> = v.into();
+  assert_eq!(tv.as_ptr(), ptr);
+  assert_eq!(tv.as_slice(), &[4, 5, 6, 7, 8, 9][..]);
+}
+
+#[test]
+fn TinyVec_from_slice() {
+  let tv: TinyVec<[i32; 4]> = TinyVec::from(&[1, 2, 3][..]);
+  assert!(tv.is_inline());
+  assert_eq!(tv.as_slice(), &[1, 2, 3][..]);
+
+  let tv: TinyVec<[i32; 4]> = TinyVec::from(&[1, 2, 3, 4][..]);
+  assert!(tv.is_inline());
+  assert_eq!(tv.as_slice(), &[1, 2, 3, 4][..]);
+
+  let tv: TinyVec<[i32; 4]> = TinyVec::from(&[1, 2, 3, 4, 5][..]);
+  assert!(tv.is_heap());
+  assert_eq!(tv.capacity(), 5);
+  assert_eq!(tv.as_slice(), &[1, 2, 3, 4, 5][..]);
+
+  let mut arr = [6, 7, 8, 9, 10, 11];
+  let tv: TinyVec<[i32; 4]> = (&mut arr[..]).into();
+  assert!(tv.is_heap());
+  assert_eq!(tv.capacity(), 6);
+  assert_eq!(tv.as_slice(), &arr[..]);
+
+  let tv: TinyVec<[i32; 4]> = (&mut arr[..2]).into();
+  assert!(tv.is_inline());
+  assert_eq!(tv.as_slice(), &[6, 7][..]);
+}
+
+#[test]
+fn TinyVec_into_vec() {
+  let tv: TinyVec<[i32; 4]> = (1..=10).collect();
+  let (ptr, cap) = (tv.as_ptr(), tv.capacity());
+  let v = tv.into_vec();
+  assert_eq!(v.as_ptr(), ptr);
+  assert_eq!(v.capacity(), cap);
+  assert_eq!(v, (1..=10).collect::<Vec<_>>());
+
+  let tv = tiny_vec!([i32; 8], 1, 2, 3);
+  let v = tv.into_vec();
+  assert_eq!(v, vec![1, 2, 3]);
+  assert_eq!(v.capacity(), 3);
+
+  let tv: TinyVec<[Tracked; 4]> = (1..=3).map(Tracked).collect();
+  let (clones_before, drops_before) = (clones(), drops());
+  let v = tv.into_vec();
+  assert_eq!((clones(), drops()), (clones_before, drops_before));
+  assert_eq!(v.iter().map(|t| t.0).collect::<Vec<_>>(), vec![1, 2, 3]);
+}
+
+#[test]
+fn TinyVec_into_boxed_slice() {
+  let mut tv: TinyVec<[i32; 2]> = TinyVec::from_vec(Vec::with_capacity(3));
+  tv.extend_from_slice(&[1, 2, 3]);
+  let ptr = tv.as_ptr();
+  let b = tv.into_boxed_slice();
+  assert_eq!(b.as_ptr(), ptr);
+  assert_eq!(&*b, &[1, 2, 3][..]);
+
+  let b = tiny_vec!([i32; 4], 4, 5).into_boxed_slice();
+  assert_eq!(&*b, &[4, 5][..]);
+}
+
+#[test]
+fn TinyVec_extend_reserves_ahead() {
+  let mut tv: TinyVec<[u32; 8]> = tiny_vec!([u32; 8], 0);
+  tv.extend(1..10_000);
+  assert_eq!(tv.len(), 10_000);
+  assert!(tv.iter().copied().eq(0..10_000));
+  // the one spill allocated everything, nothing had to grow afterwards
+  assert_eq!(tv.capacity(), 10_000);
+
+  let mut tv: TinyVec<[u32; 8]> = TinyVec::new();
+  tv.extend(0..5);
+  assert!(tv.is_inline());
+}
+
+/// Yields `0..len` but reports whatever size hint it's told to.
+struct Lying {
+  next: u32,
+  len: u32,
+  hint: usize,
+}
+impl Iterator for Lying {
+  type Item = u32;
+  fn next(&mut self) -> Option<u32> {
+    if self.next < self.len {
+      self.next += 1;
+      Some(self.next - 1)
+    } else {
+      None
+    }
+  }
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.hint, None)
+  }
+}
+
+#[test]
+fn TinyVec_extend_with_a_lying_size_hint() {
+  for &(len, hint) in [(3, 100), (100, 0), (20, 5), (0, 7)].iter() {
+    let mut tv: TinyVec<[u32; 8]> = tiny_vec!([u32; 8], 9);
+    tv.extend(Lying { next: 0, len, hint });
+    let mut expected = vec![9];
+    expected.extend(0..len);
+    assert_eq!(tv.as_slice(), expected.as_slice());
+  }
+}
+
+#[test]
+fn TinyVec_from_iter_presizes() {
+  let tv: TinyVec<[u32; 8]> = (0..8).collect();
+  assert!(tv.is_inline());
+  assert!(tv.iter().copied().eq(0..8));
+
+  let tv: TinyVec<[u32; 8]> = (0..1000).collect();
+  assert!(tv.is_heap());
+  assert_eq!(tv.capacity(), 1000);
+  assert!(tv.iter().copied().eq(0..1000));
+
+  // `filter` has a lower bound of zero, so this starts inline and grows
+  let tv: TinyVec<[u32; 8]> = (0..1000).filter(|x| x % 3 == 0).collect();
+  assert!(tv.iter().copied().eq((0..1000).step_by(3)));
+  let tv: TinyVec<[u32; 8]> = (0..20).filter(|x| x % 3 == 0).collect();
+  assert!(tv.is_inline());
+
+  // hints that are too small or too big still give the right elements
+  let tv: TinyVec<[u32; 8]> = Lying { next: 0, len: 50, hint: 2 }.collect();
+  assert!(tv.iter().copied().eq(0..50));
+  let tv: TinyVec<[u32; 8]> = Lying { next: 0, len: 2, hint: 50 }.collect();
+  assert_eq!(tv.as_slice(), &[0, 1][..]);
+}
+
+#[test]
+fn TinyVec_hash_ignores_the_variant() {
+  use std::collections::{hash_map::DefaultHasher, HashMap};
+  use std::hash::{Hash, Hasher};
+  fn hash_of<T: Hash + ?Sized>(t: &T) -> u64 {
+    let mut h = DefaultHasher::new();
+    t.hash(&mut h);
+    h.finish()
+  }
+
+  let inline = tiny_vec!([u8; 16], 1, 2, 3);
+  let mut heap = inline.clone();
+  heap.move_to_the_heap();
+  assert!(inline.is_inline() && heap.is_heap());
+  assert_eq!(inline, heap);
+  assert_eq!(inline.cmp(&heap), core::cmp::Ordering::Equal);
+  assert_eq!(hash_of(&inline), hash_of(&heap));
+  assert_eq!(hash_of(&inline), hash_of(&[1u8, 2, 3][..]));
+
+  let mut map = HashMap::new();
+  map.insert(inline, "found");
+  assert_eq!(map.get(&heap), Some(&"found"));
+  assert_eq!(map.get(&[1u8, 2, 3][..]), Some(&"found"));
+  assert_eq!(map.get(&[1u8, 2][..]), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn TinyVec_io_write() {
+  use std::io::{copy, BufWriter, Write};
+
+  let mut tv: TinyVec<[u8; 8]> = TinyVec::new();
+  assert_eq!(tv.write(b"abcdef").unwrap(), 6);
+  assert!(tv.is_inline());
+  // crossing the spill boundary is still one full write
+  assert_eq!(tv.write(b"ghijkl").unwrap(), 6);
+  assert!(tv.is_heap());
+  tv.flush().unwrap();
+  assert_eq!(tv.as_slice(), b"abcdefghijkl");
+
+  let data: Vec<u8> = (0..=255).cycle().take(5000).collect();
+  let mut tv: TinyVec<[u8; 128]> = TinyVec::new();
+  assert_eq!(copy(&mut data.as_slice(), &mut tv).unwrap(), 5000);
+  assert_eq!(tv.as_slice(), data.as_slice());
+
+  let mut tv: TinyVec<[u8; 16]> = TinyVec::new();
+  {
+    let mut w = BufWriter::with_capacity(4, &mut tv);
+    for i in 0..10 {
+      write!(w, "{},", i).unwrap();
+    }
+    w.flush().unwrap();
+  }
+  assert_eq!(tv.as_slice(), b"0,1,2,3,4,5,6,7,8,9,");
+}
+
+#[test]
+fn TinyVec_clone_skips_spare_slots() {
+  let tv: TinyVec<[Tracked; 8]> = (1..=3).map(Tracked).collect();
+  let before = clones();
+  let copy = tv.clone();
+  assert_eq!(clones(), before + 3);
+  assert_eq!(ids(&copy), vec![1, 2, 3]);
+}
+
+#[test]
+fn TinyVec_clone_from_reuses_storage() {
+  fn make(n: u32) -> TinyVec<[Tracked; 4]> {
+    (1..=n).map(Tracked).collect()
+  }
+
+  // heap destination keeps its buffer, whatever the source is
+  for &n in [2, 6, 20].iter() {
+    let mut dest = make(30);
+    let (ptr, cap) = (dest.as_ptr(), dest.capacity());
+    let source = make(n);
+    let before = clones();
+    dest.clone_from(&source);
+    assert_eq!(clones(), before + n as usize);
+    assert_eq!(ids(&dest), ids(&source));
+    assert!(dest.is_heap());
+    assert_eq!((dest.as_ptr(), dest.capacity()), (ptr, cap));
+  }
+
+  // inline destination only spills if the source doesn't fit inline
+  let mut dest = make(1);
+  dest.clone_from(&make(20).split_off(17));
+  assert!(dest.is_inline());
+  assert_eq!(ids(&dest), vec![18, 19, 20]);
+  dest.clone_from(&make(9));
+  assert!(dest.is_heap());
+  assert_eq!(dest.capacity(), 9);
+  assert_eq!(ids(&dest), (1..=9).collect::<Vec<_>>());
+}
+
+#[test]
+fn TinyVec_into_iter_matches_vec() {
+  // 0 is `next`, 1 is `next_back`, anything else is `nth(op - 2)`
+  let scripts: [&[usize]; 5] = [
+    &[0, 0, 0, 0, 0, 0, 0],
+    &[1, 1, 1, 1, 1, 1, 1],
+    &[0, 1, 0, 1, 0, 1, 0, 1],
+    &[3, 1, 2, 0, 4, 1, 0],
+    &[9, 0, 1],
+  ];
+  for script in scripts.iter() {
+    for &n in [5, 12].iter() {
+      let tv: TinyVec<[i32; 8]> = (0..n).collect();
+      let mut it = tv.into_iter();
+      let mut expected = (0..n).collect::<Vec<_>>().into_iter();
+      assert_eq!(it.len(), expected.len());
+      for &op in script.iter() {
+        let (a, b) = match op {
+          0 => (it.next(), expected.next()),
+          1 => (it.next_back(), expected.next_back()),
+          k => (it.nth(k - 2), expected.nth(k - 2)),
+        };
+        assert_eq!(a, b);
+        assert_eq!(it.size_hint(), expected.size_hint());
+        assert_eq!(it.len(), expected.len());
+      }
+      assert_eq!(it.collect::<Vec<_>>(), expected.collect::<Vec<_>>());
+    }
+  }
+
+  for &n in [5, 12].iter() {
+    let tv: TinyVec<[i32; 8]> = (0..n).collect();
+    let zipped: Vec<(i32, i32)> =
+      tv.clone().into_iter().rev().zip(tv).collect();
+    assert_eq!(zipped[0], (n - 1, 0));
+    assert_eq!(zipped.len(), n as usize);
+
+    let mut it = tiny_vec!([i32; 8], 1).into_iter();
+    assert_eq!(it.next_back(), Some(1));
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next_back(), None);
+    assert_eq!(it.next(), None);
+  }
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn TinyVec_eq_other_containers() {
+  let inline = tiny_vec!([i32; 4], 1, 2, 3);
+  let heap: TinyVec<[i32; 4]> = TinyVec::from_vec(vec![1, 2, 3]);
+  let av = array_vec!([i32; 3], 1, 2, 3);
+  for tv in [inline, heap].iter() {
+    assert_eq!(*tv, &[1, 2, 3][..]);
+    assert_eq!(*tv, vec![1, 2, 3]);
+    assert_eq!(*tv, av);
+    assert!(*tv != &[1, 2, 3, 0]);
+    assert!(*tv != &[1, 2][..]);
+    assert!(*tv != vec![1, 2, 3, 4]);
+    assert!(*tv != array_vec!([i32; 8], 1, 2));
+  }
+  assert_eq!(tiny_vec!([i32; 4], 1, 2, 3, 4), &[1, 2, 3, 4]);
+}
+
+#[cfg(feature = "rustc_1_55")]
+#[test]
+fn TinyVec_eq_arrays() {
+  let inline = tiny_vec!([i32; 4], 1, 2, 3);
+  let heap: TinyVec<[i32; 4]> = TinyVec::from_vec(vec![1, 2, 3]);
+  for tv in [inline, heap].iter() {
+    assert_eq!(*tv, [1, 2, 3]);
+    assert!(*tv != [1, 2]);
+    assert!(*tv != [1, 2, 3, 4]);
+  }
+}
+
+#[test]
+fn TinyVec_formatting_matches_ArrayVec() {
+  let av = array_vec!([i32; 4], 10, -3, 255);
+  let inline: TinyVec<[i32; 4]> = av.into();
+  let heap: TinyVec<[i32; 4]> = TinyVec::from_vec(av.to_vec());
+  for tv in [inline, heap].iter() {
+    assert_eq!(format!("{}", tv), format!("{}", av));
+    assert_eq!(format!("{:?}", tv), format!("{:?}", av));
+    assert_eq!(format!("{:b}", tv), format!("{:b}", av));
+    assert_eq!(format!("{:o}", tv), format!("{:o}", av));
+    assert_eq!(format!("{:x}", tv), format!("{:x}", av));
+    assert_eq!(format!("{:#X}", tv), format!("{:#X}", av));
+    assert_eq!(format!("{:04}", tv), "[0010, -003, 0255]");
+  }
+  assert_eq!(format!("{:x}", av), "[a, fffffffd, ff]");
+
+  let floats = array_vec!([f64; 2], 1500.0, 0.25);
+  let tv: TinyVec<[f64; 2]> = floats.into();
+  assert_eq!(format!("{:e}", tv), format!("{:e}", floats));
+  assert_eq!(format!("{:E}", tv), "[1.5E3, 2.5E-1]");
+
+  let ptrs = array_vec!([&str; 2], "a", "b");
+  let tv: TinyVec<[&str; 2]> = ptrs.into();
+  assert_eq!(format!("{:p}", tv), format!("{:p}", ptrs));
+}
+
+#[test]
+fn TinyVec_variant_accessors() {
+  let mut tv = tiny_vec!([i32; 2], 1, 2);
+  assert_eq!(tv.as_inline().map(|a| a.len()), Some(2));
+  assert!(tv.as_heap().is_none());
+  assert!(tv.as_heap_mut().is_none());
+  tv.as_inline_mut().unwrap().pop();
+  assert_eq!(tv.as_slice(), &[1][..]);
+
+  tv.extend_from_slice(&[2, 3]);
+  assert!(tv.as_inline().is_none());
+  assert!(tv.as_inline_mut().is_none());
+  assert_eq!(tv.as_heap().map(|v| v.as_ptr()), Some(tv.as_ptr()));
+  let v = tv.as_heap_mut().unwrap();
+  v.truncate(1);
+  v.shrink_to_fit();
+  assert_eq!(tv.as_slice(), &[1][..]);
+  assert!(tv.is_heap());
+  *tv.as_heap_mut().unwrap() = vec![4, 5, 6];
+  assert_eq!(tv.as_slice(), &[4, 5, 6][..]);
+}
+
 #[test]
 fn TinyVec_resize() {
   let mut tv: TinyVec<[i32; 10]> = Default::default();
   tv.resize(20, 5);
   assert_eq!(&tv[..], &[5; 20]);
 }
+
+#[test]
+fn TinyVec_resize_across_the_boundary() {
+  let mut tv: TinyVec<[Tracked; 4]> = TinyVec::new();
+  tv.resize(3, Tracked(7));
+  assert_eq!(clones(), 2);
+  assert!(tv.is_inline());
+
+  tv.resize(10, Tracked(8));
+  assert_eq!(clones(), 2 + 6);
+  assert!(tv.is_heap());
+  assert_eq!(tv.len(), 10);
+  assert!(tv.capacity() >= 10);
+  assert_eq!(tv.iter().map(|t| t.0).collect::<Vec<_>>(), {
+    let mut v = vec![7; 3];
+    v.resize(10, 8);
+    v
+  });
+
+  // shrinking below the inline capacity stays on the heap
+  tv.resize(2, Tracked(9));
+  assert_eq!(clones(), 2 + 6);
+  assert_eq!(tv.iter().map(|t| t.0).collect::<Vec<_>>(), vec![7, 7]);
+  assert!(tv.is_heap());
+}
+
+#[test]
+fn TinyVec_resize_with_order() {
+  let mut calls = 0;
+  let mut tv: TinyVec<[i32; 4]> = tiny_vec!([i32; 4], -1);
+  tv.resize_with(3, || {
+    calls += 1;
+    calls
+  });
+  assert_eq!(tv.as_slice(), &[-1, 1, 2][..]);
+  assert!(tv.is_inline());
+
+  tv.resize_with(6, || {
+    calls += 1;
+    calls
+  });
+  assert_eq!(tv.as_slice(), &[-1, 1, 2, 3, 4, 5][..]);
+  assert!(tv.is_heap());
+  assert!(tv.capacity() >= 6);
+
+  tv.resize_with(1, || unreachable!());
+  assert_eq!(tv.as_slice(), &[-1][..]);
+}
+
+#[test]
+fn tiny_vec_macro_variants() {
+  fn inline_len<A: Array>(tv: &TinyVec<A>) -> Option<usize> {
+    match tv {
+      TinyVec::Inline(a) => Some(a.len()),
+      TinyVec::Heap(_) => None,
+    }
+  }
+
+  let empty = tiny_vec!([u8; 4]);
+  assert_eq!(inline_len(&empty), Some(0));
+  assert_eq!(inline_len(&tiny_vec!(u8; 4)), Some(0));
+
+  let shorter = tiny_vec!([u8; 4], 1, 2, 3);
+  assert_eq!(inline_len(&shorter), Some(3));
+  assert_eq!(shorter.as_slice(), &[1, 2, 3][..]);
+
+  let equal = tiny_vec!(u8; 4 => 1, 2, 3, 4);
+  assert_eq!(inline_len(&equal), Some(4));
+  assert_eq!(equal.as_slice(), &[1, 2, 3, 4][..]);
+
+  let longer = tiny_vec!([String; 2], "a".to_string(), "b".into(), "c".into());
+  assert_eq!(inline_len(&longer), None);
+  match &longer {
+    TinyVec::Heap(v) => assert_eq!(v.capacity(), 3),
+    TinyVec::Inline(_) => unreachable!(),
+  }
+  assert_eq!(longer.as_slice(), &["a", "b", "c"][..]);
+}
+
+#[test]
+fn tiny_vec_macro_repeat() {
+  let inline = tiny_vec!([u8; 4] => 7; 4);
+  assert!(match inline {
+    TinyVec::Inline(_) => true,
+    TinyVec::Heap(_) => false,
+  });
+  assert_eq!(inline.as_slice(), &[7; 4][..]);
+
+  let mut evaluated = 0;
+  let heap = tiny_vec!([u8; 4] => {
+    evaluated += 1;
+    9
+  }; 5);
+  assert_eq!(evaluated, 1);
+  assert!(match heap {
+    TinyVec::Inline(_) => false,
+    TinyVec::Heap(_) => true,
+  });
+  assert_eq!(heap.as_slice(), &[9; 5][..]);
+
+  assert!(tiny_vec!([u8; 4] => 1; 0).is_empty());
+}
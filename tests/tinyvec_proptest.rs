@@ -0,0 +1,88 @@
+#![allow(bad_style)]
+
+use proptest::prelude::*;
+use tinyvec::*;
+
+/// An operation to run against both variants.
+#[derive(Debug, Clone)]
+enum Op {
+  Push(u8),
+  Pop,
+  Insert(usize, u8),
+  Remove(usize),
+  Truncate(usize),
+  RetainEven,
+  ExtendFromSlice(Vec<u8>),
+  Drain(usize, usize),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+  prop_oneof![
+    any::<u8>().prop_map(Op::Push),
+    Just(Op::Pop),
+    (any::<usize>(), any::<u8>()).prop_map(|(i, x)| Op::Insert(i, x)),
+    any::<usize>().prop_map(Op::Remove),
+    (0..12_usize).prop_map(Op::Truncate),
+    Just(Op::RetainEven),
+    proptest::collection::vec(any::<u8>(), 0..6).prop_map(Op::ExtendFromSlice),
+    (any::<usize>(), any::<usize>()).prop_map(|(a, b)| Op::Drain(a, b)),
+  ]
+}
+
+fn apply(tv: &mut TinyVec<[u8; 4]>, op: &Op) -> Vec<u8> {
+  // indexes are wrapped into range so that no op panics
+  let len = tv.len();
+  match op {
+    Op::Push(x) => tv.push(*x),
+    Op::Pop => return tv.pop().into_iter().collect(),
+    Op::Insert(i, x) => tv.insert(i % (len + 1), *x),
+    Op::Remove(i) if len > 0 => return vec![tv.remove(i % len)],
+    Op::Remove(_) => (),
+    Op::Truncate(n) => tv.truncate(*n),
+    Op::RetainEven => tv.retain(|x| x % 2 == 0),
+    Op::ExtendFromSlice(sli) => tv.extend_from_slice(sli),
+    Op::Drain(a, b) => {
+      let (a, b) = (a % (len + 1), b % (len + 1));
+      return tv.drain(a.min(b)..a.max(b)).collect();
+    }
+  }
+  Vec::new()
+}
+
+proptest! {
+  #[test]
+  fn TinyVec_variants_behave_the_same(
+    tv in any::<TinyVec<[u8; 4]>>(),
+    ops in proptest::collection::vec(op(), 0..20),
+  ) {
+    let mut heap = tv.clone();
+    heap.move_to_the_heap();
+    let mut inline = tv.clone();
+    let started_inline = inline.try_move_to_inline();
+    prop_assert_eq!(started_inline, tv.len() <= 4);
+
+    let mut reference = tv.to_vec();
+    for op in ops.iter() {
+      let from_heap = apply(&mut heap, op);
+      let from_inline = apply(&mut inline, op);
+      let expected = {
+        let mut tv: TinyVec<[u8; 4]> = TinyVec::from_vec(reference.clone());
+        let out = apply(&mut tv, op);
+        reference = tv.to_vec();
+        out
+      };
+      prop_assert_eq!(&from_heap, &expected);
+      prop_assert_eq!(&from_inline, &expected);
+      prop_assert_eq!(heap.as_slice(), reference.as_slice());
+      prop_assert_eq!(inline.as_slice(), reference.as_slice());
+    }
+  }
+
+  #[test]
+  fn TinyVec_arbitrary_lengths_straddle_inline(
+    tv in any::<TinyVec<[u8; 4]>>(),
+  ) {
+    prop_assert!(tv.len() <= 8);
+    prop_assert_eq!(tv.is_inline(), tv.len() <= 4);
+  }
+}
@@ -0,0 +1,38 @@
+#![allow(bad_style)]
+
+use rayon::prelude::*;
+use tinyvec::*;
+
+#[test]
+fn TinyVec_par_iter_map_collect_inline() {
+  let tv: TinyVec<[u32; 8]> = (0..5).collect();
+  let out: TinyVec<[u32; 8]> = tv.par_iter().map(|x| x * 2).collect();
+  assert!(out.is_inline());
+  assert_eq!(out.as_slice(), &[0, 2, 4, 6, 8][..]);
+
+  let owned: TinyVec<[u32; 8]> = out.into_par_iter().map(|x| x + 1).collect();
+  assert!(owned.is_inline());
+  assert_eq!(owned.as_slice(), &[1, 3, 5, 7, 9][..]);
+}
+
+#[test]
+fn TinyVec_par_iter_map_collect_heap() {
+  let tv: TinyVec<[u32; 8]> = (0..100).collect();
+  let out: TinyVec<[u32; 8]> = tv.par_iter().map(|x| x * 2).collect();
+  assert!(out.is_heap());
+  assert_eq!(out.to_vec(), (0..100).map(|x| x * 2).collect::<Vec<u32>>());
+
+  let owned: TinyVec<[u32; 8]> = out.into_par_iter().map(|x| x / 2).collect();
+  assert_eq!(owned.to_vec(), (0..100).collect::<Vec<u32>>());
+}
+
+#[test]
+fn TinyVec_par_iter_mut_and_extend() {
+  let mut tv: TinyVec<[u32; 4]> = (0..3).collect();
+  tv.par_iter_mut().for_each(|x| *x += 10);
+  assert_eq!(tv.as_slice(), &[10, 11, 12][..]);
+
+  tv.par_extend((0..3).into_par_iter());
+  assert!(tv.is_heap());
+  assert_eq!(tv.as_slice(), &[10, 11, 12, 0, 1, 2][..]);
+}
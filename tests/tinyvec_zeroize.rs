@@ -0,0 +1,39 @@
+#![allow(bad_style)]
+
+use tinyvec::*;
+use zeroize::Zeroize;
+
+#[test]
+fn ArrayVec_zeroize_clears_the_inactive_slots() {
+  let mut av = array_vec!([u8; 4], 1, 2, 3, 4);
+  av.set_len(2);
+  av.zeroize();
+  assert_eq!(av.into_raw_parts(), ([0; 4], 0));
+}
+
+#[test]
+fn TinyVec_zeroize_inline() {
+  let mut tv = tiny_vec!([u8; 4], 5, 6, 7);
+  tv.zeroize();
+  let arr = tv.as_inline().unwrap();
+  assert!(arr.is_empty());
+  assert_eq!(arr.clone().into_raw_parts(), ([0; 4], 0));
+}
+
+#[test]
+fn TinyVec_zeroize_after_spill() {
+  let mut tv = tiny_vec!([u8; 4], 1, 2, 3, 4);
+  tv.zeroize_spill();
+  assert!(tv.is_heap());
+  tv.extend_from_slice(&[5, 6]);
+  assert_eq!(tv.as_slice(), &[1, 2, 3, 4, 5, 6][..]);
+  let cap = tv.capacity();
+
+  tv.zeroize();
+  let v = tv.as_heap().unwrap();
+  assert!(v.is_empty());
+  assert_eq!(v.capacity(), cap);
+  // the whole allocation was written with zeroes, so it's all initialized
+  let whole = unsafe { core::slice::from_raw_parts(v.as_ptr(), cap) };
+  assert!(whole.iter().all(|&b| b == 0));
+}
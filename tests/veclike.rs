@@ -0,0 +1,67 @@
+#![allow(bad_style)]
+
+use tinyvec::*;
+
+/// Pushes `1..=n`, stopping early if the container fills up.
+fn push_up_to<V: VecLike<Item = u32>>(out: &mut V, n: u32) -> usize {
+  let mut pushed = 0;
+  for x in 1..=n {
+    if out.try_push(x).is_err() {
+      break;
+    }
+    pushed += 1;
+  }
+  pushed
+}
+
+fn exercise<V: VecLike<Item = u32> + Default>(capacity: Option<usize>) {
+  let mut v = V::default();
+  assert!(v.is_empty());
+  let pushed = push_up_to(&mut v, 10);
+  assert_eq!(pushed, capacity.unwrap_or(10).min(10));
+  assert_eq!(v.len(), pushed);
+  assert!(v.as_slice().iter().cloned().eq(1..=pushed as u32));
+
+  v.as_mut_slice()[0] = 7;
+  assert_eq!(v.as_slice()[0], 7);
+  assert_eq!(v.pop(), Some(pushed as u32));
+  v.truncate(2);
+  assert_eq!(v.as_slice(), &[7, 2][..]);
+  v.extend_from_slice(&[3]);
+  v.push(4);
+  assert_eq!(v.as_slice(), &[7, 2, 3, 4][..]);
+  v.clear();
+  assert!(v.is_empty());
+  assert_eq!(v.pop(), None);
+}
+
+#[test]
+fn VecLike_ArrayVec() {
+  exercise::<ArrayVec<[u32; 4]>>(Some(4));
+  exercise::<ArrayVec<[u32; 16]>>(Some(16));
+}
+
+#[test]
+fn VecLike_SmallArrayVec() {
+  exercise::<SmallArrayVec<[u32; 4], u8>>(Some(4));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn VecLike_TinyVec() {
+  exercise::<TinyVec<[u32; 4]>>(None);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn VecLike_Vec() {
+  exercise::<Vec<u32>>(None);
+}
+
+#[test]
+#[should_panic]
+fn VecLike_push_overflow() {
+  let mut av: ArrayVec<[u32; 1]> = ArrayVec::new();
+  VecLike::push(&mut av, 1);
+  VecLike::push(&mut av, 2);
+}